@@ -0,0 +1,43 @@
+use crate::{expr::Expr, label::Label};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Data<'a> {
+    Integer(i128),
+    Float(f64),
+    Expr(Expr<'a>),
+}
+impl From<i64> for Data<'static> {
+    fn from(value: i64) -> Self {
+        Self::Integer(value.into())
+    }
+}
+impl From<u64> for Data<'static> {
+    fn from(value: u64) -> Self {
+        Self::Integer(value.into())
+    }
+}
+impl From<f64> for Data<'static> {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+impl From<f32> for Data<'static> {
+    fn from(value: f32) -> Self {
+        Self::Float(value.into())
+    }
+}
+impl<'a> From<Expr<'a>> for Data<'a> {
+    fn from(value: Expr<'a>) -> Self {
+        Self::Expr(value)
+    }
+}
+impl<'a> From<&'a str> for Data<'a> {
+    fn from(value: &'a str) -> Self {
+        Self::Expr(Expr::Symbol(value))
+    }
+}
+impl<'a> From<&'a Label> for Data<'a> {
+    fn from(value: &'a Label) -> Self {
+        Self::Expr(Expr::Symbol(value.name()))
+    }
+}
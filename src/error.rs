@@ -0,0 +1,172 @@
+use std::{fmt, io};
+
+use crate::{
+    mem::{Addressing, MemError},
+    nasm::render_operand,
+    operand::Operand,
+    writer::{Bits, Condition, Feature, FpType},
+};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Invalid(Invalid),
+    Label(LabelError),
+    Labels(Vec<LabelError>),
+}
+impl Error {
+    pub fn invalid(mnemonic: &str, operands: &[&Operand], kind: InvalidKind) -> Self {
+        Self::Invalid(Invalid {
+            mnemonic: mnemonic.to_string(),
+            operands: operands.iter().map(|op| render_operand(op)).collect(),
+            kind,
+        })
+    }
+}
+impl From<io::Error> for Error {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "{err}"),
+            Self::Invalid(invalid) => write!(f, "{invalid}"),
+            Self::Label(err) => write!(f, "{err}"),
+            Self::Labels(errs) => {
+                for (i, err) in errs.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, "; ")?;
+                    }
+                    write!(f, "{err}")?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Invalid(_) | Self::Label(_) | Self::Labels(_) => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Invalid {
+    pub mnemonic: String,
+    pub operands: Vec<String>,
+    pub kind: InvalidKind,
+}
+impl fmt::Display for Invalid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = &self.mnemonic;
+        match &self.kind {
+            InvalidKind::MemoryToMemory => write!(f, "{name} cannot take two memory operands")?,
+            InvalidKind::SizeMismatch => write!(f, "{name} operand sizes do not match")?,
+            InvalidKind::ImmediateTooWide => write!(f, "{name} immediate is too wide")?,
+            InvalidKind::AmbiguousSize => {
+                write!(f, "{name} operand size is ambiguous, annotate the memory operand")?
+            }
+            InvalidKind::IllegalOperand => write!(f, "{name} has no form for these operands")?,
+            InvalidKind::IllegalCondition(cc) => write!(f, "{name} cannot use condition {cc:?}")?,
+            InvalidKind::HighByteConflict => write!(
+                f,
+                "{name} mixes high byte registers with registers needing REX"
+            )?,
+            InvalidKind::NotInMode(bits) => write!(f, "{name} is not available in {bits:?} mode")?,
+            InvalidKind::MissingFeature(feature) => {
+                write!(f, "{name} needs the {feature:?} feature to be enabled")?
+            }
+            InvalidKind::Mem(err) => write!(f, "{name} has an invalid address, {err}")?,
+            InvalidKind::NoHighByte => write!(f, "{name} uses a register with no high byte")?,
+            InvalidKind::AddressingWithRegisters(addressing) => write!(
+                f,
+                "{name} cannot use base or index registers with {addressing:?} addressing"
+            )?,
+            InvalidKind::Abs64NeedsAccumulator => write!(
+                f,
+                "{name} cannot use a 64-bit absolute address outside mov with the accumulator"
+            )?,
+            InvalidKind::IllegalPair => write!(
+                f,
+                "{name} needs two distinct 64-bit registers other than rsp"
+            )?,
+            InvalidKind::NoSegmentForm => write!(f, "{name} has no segment register form")?,
+            InvalidKind::NeedsQWord => write!(f, "{name} needs a 64-bit register")?,
+            InvalidKind::NeedsWideRegister => write!(f, "{name} needs a 32 or 64-bit register")?,
+            InvalidKind::NoByteForm => write!(f, "{name} has no 8-bit form")?,
+            InvalidKind::AccumulatorOnly => {
+                write!(f, "{name} only transfers through al, ax or eax")?
+            }
+            InvalidKind::IllegalPort => {
+                write!(f, "{name} port must be dx or an 8-bit immediate")?
+            }
+            InvalidKind::NeedsXmm => write!(f, "{name} takes xmm operands")?,
+            InvalidKind::IllegalFpType(ty) => write!(f, "{name} has no {ty:?} form")?,
+        }
+        if !self.operands.is_empty() {
+            write!(f, ": {}", self.operands.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum InvalidKind {
+    MemoryToMemory,
+    SizeMismatch,
+    ImmediateTooWide,
+    AmbiguousSize,
+    IllegalOperand,
+    IllegalCondition(Condition),
+    HighByteConflict,
+    NotInMode(Bits),
+    MissingFeature(Feature),
+    Mem(MemError),
+    NoHighByte,
+    AddressingWithRegisters(Addressing),
+    Abs64NeedsAccumulator,
+    IllegalPair,
+    NoSegmentForm,
+    NeedsQWord,
+    NeedsWideRegister,
+    NoByteForm,
+    AccumulatorOnly,
+    IllegalPort,
+    NeedsXmm,
+    IllegalFpType(FpType),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LabelError {
+    AlreadyBound(String),
+    Undefined(String),
+    Duplicate(String),
+    UnusedLocal(String),
+    NestedFunction(String),
+    NotInFunction,
+    UnclosedFunction(String),
+}
+impl fmt::Display for LabelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AlreadyBound(name) => write!(f, "label {name} is already bound"),
+            Self::Undefined(name) => write!(f, "label {name} is never defined"),
+            Self::Duplicate(name) => write!(f, "label {name} is defined more than once"),
+            Self::UnusedLocal(name) => write!(f, "local label {name} is never referenced"),
+            Self::NestedFunction(name) => {
+                write!(f, "function {name} begins inside another function")
+            }
+            Self::NotInFunction => write!(f, "end of function outside of any function"),
+            Self::UnclosedFunction(name) => write!(f, "function {name} is never ended"),
+        }
+    }
+}
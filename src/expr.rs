@@ -0,0 +1,124 @@
+use std::ops::{Add, BitAnd, BitOr, Div, Mul, Shl, Shr, Sub};
+
+use crate::label::Label;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Expr<'a> {
+    Integer(i64),
+    Symbol(&'a str),
+    Here,
+    SectionStart,
+    Binary(BinOp, Box<Expr<'a>>, Box<Expr<'a>>),
+}
+impl<'a> Expr<'a> {
+    pub fn int(value: i64) -> Self {
+        Self::Integer(value)
+    }
+    pub fn sym(name: &'a str) -> Self {
+        Self::Symbol(name)
+    }
+    pub fn here() -> Self {
+        Self::Here
+    }
+    pub fn section_start() -> Self {
+        Self::SectionStart
+    }
+    pub fn binary(op: BinOp, lhs: impl Into<Expr<'a>>, rhs: impl Into<Expr<'a>>) -> Self {
+        Self::Binary(op, Box::new(lhs.into()), Box::new(rhs.into()))
+    }
+}
+impl From<i64> for Expr<'static> {
+    fn from(value: i64) -> Self {
+        Self::Integer(value)
+    }
+}
+impl<'a> From<&'a str> for Expr<'a> {
+    fn from(value: &'a str) -> Self {
+        Self::Symbol(value)
+    }
+}
+impl<'a> From<&'a String> for Expr<'a> {
+    fn from(value: &'a String) -> Self {
+        Self::Symbol(value)
+    }
+}
+impl<'a> From<&'a Label> for Expr<'a> {
+    fn from(value: &'a Label) -> Self {
+        Self::Symbol(value.name())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Shl,
+    Shr,
+    And,
+    Or,
+}
+impl BinOp {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::Add => "+",
+            Self::Sub => "-",
+            Self::Mul => "*",
+            Self::Div => "/",
+            Self::Shl => "<<",
+            Self::Shr => ">>",
+            Self::And => "&",
+            Self::Or => "|",
+        }
+    }
+}
+
+impl<'a, T: Into<Expr<'a>>> Add<T> for Expr<'a> {
+    type Output = Self;
+    fn add(self, rhs: T) -> Self::Output {
+        Self::binary(BinOp::Add, self, rhs)
+    }
+}
+impl<'a, T: Into<Expr<'a>>> Sub<T> for Expr<'a> {
+    type Output = Self;
+    fn sub(self, rhs: T) -> Self::Output {
+        Self::binary(BinOp::Sub, self, rhs)
+    }
+}
+impl<'a, T: Into<Expr<'a>>> Mul<T> for Expr<'a> {
+    type Output = Self;
+    fn mul(self, rhs: T) -> Self::Output {
+        Self::binary(BinOp::Mul, self, rhs)
+    }
+}
+impl<'a, T: Into<Expr<'a>>> Div<T> for Expr<'a> {
+    type Output = Self;
+    fn div(self, rhs: T) -> Self::Output {
+        Self::binary(BinOp::Div, self, rhs)
+    }
+}
+impl<'a, T: Into<Expr<'a>>> Shl<T> for Expr<'a> {
+    type Output = Self;
+    fn shl(self, rhs: T) -> Self::Output {
+        Self::binary(BinOp::Shl, self, rhs)
+    }
+}
+impl<'a, T: Into<Expr<'a>>> Shr<T> for Expr<'a> {
+    type Output = Self;
+    fn shr(self, rhs: T) -> Self::Output {
+        Self::binary(BinOp::Shr, self, rhs)
+    }
+}
+impl<'a, T: Into<Expr<'a>>> BitAnd<T> for Expr<'a> {
+    type Output = Self;
+    fn bitand(self, rhs: T) -> Self::Output {
+        Self::binary(BinOp::And, self, rhs)
+    }
+}
+impl<'a, T: Into<Expr<'a>>> BitOr<T> for Expr<'a> {
+    type Output = Self;
+    fn bitor(self, rhs: T) -> Self::Output {
+        Self::binary(BinOp::Or, self, rhs)
+    }
+}
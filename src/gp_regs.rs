@@ -20,6 +20,21 @@ impl Reg {
     pub fn mem(self) -> Mem<'static> {
         Mem::new() + self
     }
+
+    pub fn is_high_byte(self) -> bool {
+        self.size == RSize::HighByte
+    }
+    pub fn needs_rex(self) -> bool {
+        match self.size {
+            RSize::HighByte => false,
+            RSize::Byte => {
+                self.name.is_extended()
+                    || matches!(self.name, RName::SI | RName::DI | RName::BP | RName::SP)
+            }
+            RSize::Word | RSize::DWord => self.name.is_extended(),
+            RSize::QWord => true,
+        }
+    }
 }
 impl Add<RSize> for Reg {
     type Output = Self;
@@ -53,11 +68,37 @@ pub enum RName {
     R13,
     R14,
     R15,
+    R16,
+    R17,
+    R18,
+    R19,
+    R20,
+    R21,
+    R22,
+    R23,
+    R24,
+    R25,
+    R26,
+    R27,
+    R28,
+    R29,
+    R30,
+    R31,
 }
 impl RName {
     pub fn with_size(self, size: RSize) -> Reg {
         Reg { name: self, size }
     }
+
+    pub fn is_apx(self) -> bool {
+        self as u8 >= RName::R16 as u8
+    }
+    pub fn is_extended(self) -> bool {
+        !matches!(
+            self,
+            RName::A | RName::B | RName::C | RName::D | RName::DI | RName::SI | RName::BP | RName::SP
+        )
+    }
 }
 impl Add<RSize> for RName {
     type Output = Reg;
@@ -69,6 +110,7 @@ impl Add<RSize> for RName {
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum RSize {
     Byte,
+    HighByte,
     Word,
     DWord,
     QWord,
@@ -89,6 +131,10 @@ pub const AL: Reg = Reg {
     name: RName::A,
     size: RSize::Byte,
 };
+pub const AH: Reg = Reg {
+    name: RName::A,
+    size: RSize::HighByte,
+};
 pub const AX: Reg = Reg {
     name: RName::A,
     size: RSize::Word,
@@ -106,6 +152,10 @@ pub const BL: Reg = Reg {
     name: RName::B,
     size: RSize::Byte,
 };
+pub const BH: Reg = Reg {
+    name: RName::B,
+    size: RSize::HighByte,
+};
 pub const BX: Reg = Reg {
     name: RName::B,
     size: RSize::Word,
@@ -123,6 +173,10 @@ pub const CL: Reg = Reg {
     name: RName::C,
     size: RSize::Byte,
 };
+pub const CH: Reg = Reg {
+    name: RName::C,
+    size: RSize::HighByte,
+};
 pub const CX: Reg = Reg {
     name: RName::C,
     size: RSize::Word,
@@ -140,6 +194,10 @@ pub const DL: Reg = Reg {
     name: RName::D,
     size: RSize::Byte,
 };
+pub const DH: Reg = Reg {
+    name: RName::D,
+    size: RSize::HighByte,
+};
 pub const DX: Reg = Reg {
     name: RName::D,
     size: RSize::Word,
@@ -356,3 +414,275 @@ pub const R15: Reg = Reg {
     name: RName::R15,
     size: RSize::QWord,
 };
+
+pub const R16B: Reg = Reg {
+    name: RName::R16,
+    size: RSize::Byte,
+};
+pub const R16W: Reg = Reg {
+    name: RName::R16,
+    size: RSize::Word,
+};
+pub const R16D: Reg = Reg {
+    name: RName::R16,
+    size: RSize::DWord,
+};
+pub const R16: Reg = Reg {
+    name: RName::R16,
+    size: RSize::QWord,
+};
+
+pub const R17B: Reg = Reg {
+    name: RName::R17,
+    size: RSize::Byte,
+};
+pub const R17W: Reg = Reg {
+    name: RName::R17,
+    size: RSize::Word,
+};
+pub const R17D: Reg = Reg {
+    name: RName::R17,
+    size: RSize::DWord,
+};
+pub const R17: Reg = Reg {
+    name: RName::R17,
+    size: RSize::QWord,
+};
+
+pub const R18B: Reg = Reg {
+    name: RName::R18,
+    size: RSize::Byte,
+};
+pub const R18W: Reg = Reg {
+    name: RName::R18,
+    size: RSize::Word,
+};
+pub const R18D: Reg = Reg {
+    name: RName::R18,
+    size: RSize::DWord,
+};
+pub const R18: Reg = Reg {
+    name: RName::R18,
+    size: RSize::QWord,
+};
+
+pub const R19B: Reg = Reg {
+    name: RName::R19,
+    size: RSize::Byte,
+};
+pub const R19W: Reg = Reg {
+    name: RName::R19,
+    size: RSize::Word,
+};
+pub const R19D: Reg = Reg {
+    name: RName::R19,
+    size: RSize::DWord,
+};
+pub const R19: Reg = Reg {
+    name: RName::R19,
+    size: RSize::QWord,
+};
+
+pub const R20B: Reg = Reg {
+    name: RName::R20,
+    size: RSize::Byte,
+};
+pub const R20W: Reg = Reg {
+    name: RName::R20,
+    size: RSize::Word,
+};
+pub const R20D: Reg = Reg {
+    name: RName::R20,
+    size: RSize::DWord,
+};
+pub const R20: Reg = Reg {
+    name: RName::R20,
+    size: RSize::QWord,
+};
+
+pub const R21B: Reg = Reg {
+    name: RName::R21,
+    size: RSize::Byte,
+};
+pub const R21W: Reg = Reg {
+    name: RName::R21,
+    size: RSize::Word,
+};
+pub const R21D: Reg = Reg {
+    name: RName::R21,
+    size: RSize::DWord,
+};
+pub const R21: Reg = Reg {
+    name: RName::R21,
+    size: RSize::QWord,
+};
+
+pub const R22B: Reg = Reg {
+    name: RName::R22,
+    size: RSize::Byte,
+};
+pub const R22W: Reg = Reg {
+    name: RName::R22,
+    size: RSize::Word,
+};
+pub const R22D: Reg = Reg {
+    name: RName::R22,
+    size: RSize::DWord,
+};
+pub const R22: Reg = Reg {
+    name: RName::R22,
+    size: RSize::QWord,
+};
+
+pub const R23B: Reg = Reg {
+    name: RName::R23,
+    size: RSize::Byte,
+};
+pub const R23W: Reg = Reg {
+    name: RName::R23,
+    size: RSize::Word,
+};
+pub const R23D: Reg = Reg {
+    name: RName::R23,
+    size: RSize::DWord,
+};
+pub const R23: Reg = Reg {
+    name: RName::R23,
+    size: RSize::QWord,
+};
+
+pub const R24B: Reg = Reg {
+    name: RName::R24,
+    size: RSize::Byte,
+};
+pub const R24W: Reg = Reg {
+    name: RName::R24,
+    size: RSize::Word,
+};
+pub const R24D: Reg = Reg {
+    name: RName::R24,
+    size: RSize::DWord,
+};
+pub const R24: Reg = Reg {
+    name: RName::R24,
+    size: RSize::QWord,
+};
+
+pub const R25B: Reg = Reg {
+    name: RName::R25,
+    size: RSize::Byte,
+};
+pub const R25W: Reg = Reg {
+    name: RName::R25,
+    size: RSize::Word,
+};
+pub const R25D: Reg = Reg {
+    name: RName::R25,
+    size: RSize::DWord,
+};
+pub const R25: Reg = Reg {
+    name: RName::R25,
+    size: RSize::QWord,
+};
+
+pub const R26B: Reg = Reg {
+    name: RName::R26,
+    size: RSize::Byte,
+};
+pub const R26W: Reg = Reg {
+    name: RName::R26,
+    size: RSize::Word,
+};
+pub const R26D: Reg = Reg {
+    name: RName::R26,
+    size: RSize::DWord,
+};
+pub const R26: Reg = Reg {
+    name: RName::R26,
+    size: RSize::QWord,
+};
+
+pub const R27B: Reg = Reg {
+    name: RName::R27,
+    size: RSize::Byte,
+};
+pub const R27W: Reg = Reg {
+    name: RName::R27,
+    size: RSize::Word,
+};
+pub const R27D: Reg = Reg {
+    name: RName::R27,
+    size: RSize::DWord,
+};
+pub const R27: Reg = Reg {
+    name: RName::R27,
+    size: RSize::QWord,
+};
+
+pub const R28B: Reg = Reg {
+    name: RName::R28,
+    size: RSize::Byte,
+};
+pub const R28W: Reg = Reg {
+    name: RName::R28,
+    size: RSize::Word,
+};
+pub const R28D: Reg = Reg {
+    name: RName::R28,
+    size: RSize::DWord,
+};
+pub const R28: Reg = Reg {
+    name: RName::R28,
+    size: RSize::QWord,
+};
+
+pub const R29B: Reg = Reg {
+    name: RName::R29,
+    size: RSize::Byte,
+};
+pub const R29W: Reg = Reg {
+    name: RName::R29,
+    size: RSize::Word,
+};
+pub const R29D: Reg = Reg {
+    name: RName::R29,
+    size: RSize::DWord,
+};
+pub const R29: Reg = Reg {
+    name: RName::R29,
+    size: RSize::QWord,
+};
+
+pub const R30B: Reg = Reg {
+    name: RName::R30,
+    size: RSize::Byte,
+};
+pub const R30W: Reg = Reg {
+    name: RName::R30,
+    size: RSize::Word,
+};
+pub const R30D: Reg = Reg {
+    name: RName::R30,
+    size: RSize::DWord,
+};
+pub const R30: Reg = Reg {
+    name: RName::R30,
+    size: RSize::QWord,
+};
+
+pub const R31B: Reg = Reg {
+    name: RName::R31,
+    size: RSize::Byte,
+};
+pub const R31W: Reg = Reg {
+    name: RName::R31,
+    size: RSize::Word,
+};
+pub const R31D: Reg = Reg {
+    name: RName::R31,
+    size: RSize::DWord,
+};
+pub const R31: Reg = Reg {
+    name: RName::R31,
+    size: RSize::QWord,
+};
@@ -0,0 +1,111 @@
+use crate::{
+    Error, Result,
+    error::InvalidKind,
+    gp_regs::*,
+    operand::Operand,
+    writer::{Condition, X86Writer},
+};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ImplicitWrites {
+    pub regs: &'static [Reg],
+    pub flags: bool,
+}
+
+pub const CPUID: ImplicitWrites = ImplicitWrites {
+    regs: &[RAX, RBX, RCX, RDX],
+    flags: false,
+};
+pub const RDTSC: ImplicitWrites = ImplicitWrites {
+    regs: &[RAX, RDX],
+    flags: false,
+};
+pub const RDTSCP: ImplicitWrites = ImplicitWrites {
+    regs: &[RAX, RCX, RDX],
+    flags: false,
+};
+pub const RDPID: ImplicitWrites = ImplicitWrites {
+    regs: &[],
+    flags: false,
+};
+pub const RDRAND: ImplicitWrites = ImplicitWrites {
+    regs: &[],
+    flags: true,
+};
+pub const RDSEED: ImplicitWrites = ImplicitWrites {
+    regs: &[],
+    flags: true,
+};
+pub const XGETBV: ImplicitWrites = ImplicitWrites {
+    regs: &[RAX, RDX],
+    flags: false,
+};
+pub const PAUSE: ImplicitWrites = ImplicitWrites {
+    regs: &[],
+    flags: false,
+};
+pub const SERIALIZE: ImplicitWrites = ImplicitWrites {
+    regs: &[],
+    flags: false,
+};
+pub const LFENCE: ImplicitWrites = ImplicitWrites {
+    regs: &[],
+    flags: false,
+};
+pub const MFENCE: ImplicitWrites = ImplicitWrites {
+    regs: &[],
+    flags: false,
+};
+
+pub fn emit_rdrand_retry(
+    w: &mut impl X86Writer,
+    rd: Reg,
+    counter: Reg,
+    retries: u32,
+    fail: &str,
+) -> Result<()> {
+    emit_retry(w, false, rd, counter, retries, fail)
+}
+pub fn emit_rdseed_retry(
+    w: &mut impl X86Writer,
+    rd: Reg,
+    counter: Reg,
+    retries: u32,
+    fail: &str,
+) -> Result<()> {
+    emit_retry(w, true, rd, counter, retries, fail)
+}
+
+fn emit_retry(
+    w: &mut impl X86Writer,
+    seed: bool,
+    rd: Reg,
+    counter: Reg,
+    retries: u32,
+    fail: &str,
+) -> Result<()> {
+    if retries == 0 {
+        let name = if seed { "rdseed" } else { "rdrand" };
+        let ops = [&Operand::from(retries)];
+        return Err(Error::invalid(name, &ops, InvalidKind::IllegalOperand));
+    }
+
+    let retry = w.new_label();
+    let done = w.new_label();
+
+    w.mov(counter.with_size(RSize::DWord), retries)?;
+    w.bind(&retry)?;
+    if seed {
+        w.rdseed(rd)?;
+    } else {
+        w.rdrand(rd)?;
+    }
+    w.jcc(Condition::C, &done)?;
+    if seed {
+        w.pause()?;
+    }
+    w.dec(counter.with_size(RSize::DWord))?;
+    w.jcc(Condition::NZ, &retry)?;
+    w.jmp(fail)?;
+    w.bind(&done)
+}
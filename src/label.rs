@@ -0,0 +1,17 @@
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Label {
+    id: usize,
+    name: String,
+}
+impl Label {
+    pub(crate) fn new(id: usize, name: String) -> Self {
+        Self { id, name }
+    }
+
+    pub fn id(&self) -> usize {
+        self.id
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
@@ -1,5 +1,18 @@
+pub mod data;
+pub mod error;
+pub mod expr;
 pub mod gp_regs;
+pub mod implicit;
+pub mod label;
 pub mod mem;
+pub mod mitigation;
 pub mod nasm;
 pub mod operand;
+pub mod switch;
+pub mod symbol;
+pub mod sys_regs;
+pub mod typed;
+pub mod vec_regs;
 pub mod writer;
+
+pub use error::{Error, Result};
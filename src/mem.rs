@@ -1,8 +1,12 @@
 use crate::{
-    gp_regs::{RSize, Reg},
+    expr::Expr,
+    gp_regs::{RName, RSize, Reg},
+    label::Label,
     operand::OSize,
+    sys_regs::SReg,
+    writer::Bits,
 };
-use std::ops::Add;
+use std::{fmt, ops::Add};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Mem<'a> {
@@ -11,6 +15,11 @@ pub struct Mem<'a> {
     pub offset: i64,
     pub label: Option<&'a str>,
     pub size: Option<OSize>,
+    pub segment: Option<SReg>,
+    /// `None` leaves the choice to the assembler. `NasmWriter::emit_preamble` emits
+    /// `default rel` in 64-bit mode, so there an unmarked label is RIP-relative.
+    pub addressing: Option<Addressing>,
+    pub expr: Option<&'a Expr<'a>>,
 }
 impl Mem<'static> {
     pub fn new() -> Self {
@@ -20,9 +29,17 @@ impl Mem<'static> {
             offset: 0,
             label: None,
             size: None,
+            segment: None,
+            addressing: None,
+            expr: None,
         }
     }
 }
+impl Default for Mem<'static> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 impl<'a> Mem<'a> {
     pub fn based(mut self, base: Reg) -> Self {
         self.base = Some(base);
@@ -39,6 +56,115 @@ impl<'a> Mem<'a> {
         self.label = Some(label);
         self
     }
+    pub fn displaced(mut self, expr: &'a Expr<'a>) -> Self {
+        self.expr = Some(expr);
+        self
+    }
+    pub fn segmented(mut self, segment: SReg) -> Self {
+        self.segment = Some(segment);
+        self
+    }
+    pub fn rip_relative(self) -> Self {
+        self.addressed(Addressing::Rel)
+    }
+    pub fn absolute(self) -> Self {
+        self.addressed(Addressing::Abs32)
+    }
+    pub fn absolute64(self) -> Self {
+        self.addressed(Addressing::Abs64)
+    }
+    pub fn addressed(mut self, addressing: Addressing) -> Self {
+        self.addressing = Some(addressing);
+        self
+    }
+
+    pub fn validate(mut self, bits: Bits) -> Result<Self, MemError> {
+        match self.index {
+            Some((index, Scale::One)) if self.base.is_none() => {
+                self.base = Some(index);
+                self.index = None;
+            }
+            Some((index, Scale::Two)) if self.base.is_none() && index.name != RName::SP => {
+                self.base = Some(index);
+                self.index = Some((index, Scale::One));
+            }
+            _ => (),
+        }
+        if let (Some(base), Some((index, Scale::One))) = (self.base, self.index)
+            && index.name == RName::SP
+            && base.name != RName::SP
+        {
+            self.base = Some(index);
+            self.index = Some((base, Scale::One));
+        }
+
+        if let Some((index, _)) = self.index
+            && index.name == RName::SP
+        {
+            return Err(MemError::StackPointerIndex);
+        }
+        if let (Some(base), Some((index, _))) = (self.base, self.index)
+            && base.size != index.size
+        {
+            return Err(MemError::MixedWidths(base.size, index.size));
+        }
+
+        match (bits, self.address_size()) {
+            (_, None | Some(RSize::DWord)) => (),
+            (_, Some(size @ (RSize::Byte | RSize::HighByte))) => {
+                return Err(MemError::IllegalAddressSize(size));
+            }
+            (Bits::Bits64, Some(RSize::Word)) => {
+                return Err(MemError::IllegalAddressSize(RSize::Word));
+            }
+            (_, Some(RSize::Word)) if !self.is_valid_16bit_form() => {
+                return Err(MemError::Invalid16BitForm);
+            }
+            (Bits::Bits16 | Bits::Bits32, Some(RSize::QWord)) => {
+                return Err(MemError::IllegalAddressSize(RSize::QWord));
+            }
+            _ => (),
+        }
+
+        let displacement_fits = match (self.addressing, self.address_size()) {
+            (Some(Addressing::Abs64), _) => true,
+            (_, Some(RSize::Word)) => (-0x8000..=0xFFFF).contains(&self.offset),
+            (_, None) if bits != Bits::Bits64 => {
+                (-0x8000_0000..=0xFFFF_FFFF).contains(&self.offset)
+            }
+            _ => i32::try_from(self.offset).is_ok(),
+        };
+        if !displacement_fits {
+            return Err(MemError::DisplacementTooWide(self.offset));
+        }
+
+        Ok(self)
+    }
+
+    pub fn address_size(&self) -> Option<RSize> {
+        self.base
+            .or(self.index.map(|(index, _)| index))
+            .map(|reg| reg.size)
+    }
+    pub fn is_valid_16bit_form(&self) -> bool {
+        use crate::gp_regs::{BP, BX, DI, SI};
+
+        let scaled = self.index.is_some_and(|(_, scale)| scale != Scale::One);
+        let index = self.index.map(|(index, _)| index);
+        let regs = match (self.base, index) {
+            (None, Some(b)) => (Some(b), None),
+            regs => regs,
+        };
+        let valid = matches!(
+            regs,
+            (None, None)
+                | (Some(BX | BP | SI | DI), None)
+                | (Some(BX | BP), Some(SI | DI))
+                | (Some(SI | DI), Some(BX | BP))
+        );
+
+        valid && !scaled
+    }
 }
 impl<'a> Add<Reg> for Mem<'a> {
     type Output = Self;
@@ -116,6 +242,19 @@ impl<'a> Add<&'a String> for Mem<'a> {
         self
     }
 }
+impl<'a> Add<&'a Label> for Mem<'a> {
+    type Output = Self;
+    fn add(mut self, rhs: &'a Label) -> Self::Output {
+        self.label = Some(rhs.name());
+        self
+    }
+}
+impl<'a> Add<&'a Expr<'a>> for Mem<'a> {
+    type Output = Self;
+    fn add(self, rhs: &'a Expr<'a>) -> Self::Output {
+        self.displaced(rhs)
+    }
+}
 impl<'a> Add<OSize> for Mem<'a> {
     type Output = Self;
     fn add(mut self, rhs: OSize) -> Self::Output {
@@ -123,6 +262,18 @@ impl<'a> Add<OSize> for Mem<'a> {
         self
     }
 }
+impl<'a> Add<SReg> for Mem<'a> {
+    type Output = Self;
+    fn add(self, rhs: SReg) -> Self::Output {
+        self.segmented(rhs)
+    }
+}
+impl<'a> Add<Addressing> for Mem<'a> {
+    type Output = Self;
+    fn add(self, rhs: Addressing) -> Self::Output {
+        self.addressed(rhs)
+    }
+}
 impl<'a> Add<RSize> for Mem<'a> {
     type Output = Self;
     fn add(self, rhs: RSize) -> Self::Output {
@@ -137,3 +288,45 @@ pub enum Scale {
     Four,
     Eight,
 }
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Addressing {
+    Rel,
+    Abs32,
+    Abs64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MemError {
+    StackPointerIndex,
+    MixedWidths(RSize, RSize),
+    IllegalAddressSize(RSize),
+    Invalid16BitForm,
+    DisplacementTooWide(i64),
+}
+impl fmt::Display for MemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::StackPointerIndex => write!(f, "the stack pointer cannot be used as an index"),
+            Self::MixedWidths(base, index) => {
+                write!(
+                    f,
+                    "base ({base:?}) and index ({index:?}) have different widths"
+                )
+            }
+            Self::IllegalAddressSize(size) => {
+                write!(
+                    f,
+                    "{size:?} registers cannot be used for addressing in this mode"
+                )
+            }
+            Self::Invalid16BitForm => write!(
+                f,
+                "16-bit addresses must be a combination of bx or bp with si or di, unscaled"
+            ),
+            Self::DisplacementTooWide(offset) => {
+                write!(f, "displacement {offset} is too wide for the address size")
+            }
+        }
+    }
+}
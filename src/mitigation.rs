@@ -0,0 +1,39 @@
+use crate::{
+    Result,
+    gp_regs::{RName, RSP, Reg},
+    nasm::check_qword,
+    writer::X86Writer,
+};
+
+pub fn thunk_name(reg: Reg) -> Result<String> {
+    check_qword("retpoline", reg)?;
+    let reg_name = match reg.name {
+        RName::A => "rax".to_string(),
+        RName::B => "rbx".to_string(),
+        RName::C => "rcx".to_string(),
+        RName::D => "rdx".to_string(),
+        RName::DI => "rdi".to_string(),
+        RName::SI => "rsi".to_string(),
+        RName::BP => "rbp".to_string(),
+        RName::SP => "rsp".to_string(),
+        other => format!("r{}", other as u8),
+    };
+
+    Ok(format!("__x86_indirect_thunk_{reg_name}"))
+}
+
+pub fn emit_retpoline_thunk(w: &mut impl X86Writer, reg: Reg) -> Result<()> {
+    let name = thunk_name(reg)?;
+    let capture = format!("{name}.capture");
+    let setup = format!("{name}.setup");
+
+    w.label(&name)?;
+    w.call(&setup)?;
+    w.label(&capture)?;
+    w.pause()?;
+    w.lfence()?;
+    w.jmp(&capture)?;
+    w.label(&setup)?;
+    w.mov(RSP.mem(), reg)?;
+    w.ret()
+}
@@ -1,17 +1,57 @@
-use std::io::{self, Write};
+use std::collections::HashSet;
+use std::io::Write;
 
+use crate::data::Data;
+use crate::error::{Error, InvalidKind, LabelError, Result};
+use crate::expr::Expr;
+use crate::label::Label;
 use crate::gp_regs::*;
-use crate::mem::{Mem, Scale};
-use crate::operand::{OSize, Operand};
-use crate::writer::Condition;
+use crate::mem::{Addressing, Mem, Scale};
+use crate::mitigation;
+use crate::operand::{Branch, Distance, FarPtr, OSize, Operand};
+use crate::symbol::{Symbol, SymbolKind, SymbolSize, Visibility};
+use crate::sys_regs::{CReg, DReg, SReg};
+use crate::vec_regs::{VReg, VSize, XMM0};
+use crate::writer::{Bits, Condition, Feature, FpType};
 use crate::writer::X86Writer;
 
 pub struct NasmWriter<O> {
     out: O,
+    bits: Bits,
+    features: HashSet<Feature>,
+    labels: usize,
+    bound: HashSet<usize>,
+    defined: HashSet<String>,
+    duplicates: Vec<String>,
+    referenced: HashSet<String>,
+    externs: HashSet<String>,
+    locals: HashSet<String>,
+    scope: Option<String>,
+    function: Option<String>,
 }
 impl<O: Write> NasmWriter<O> {
     pub fn new(out: O) -> Self {
-        Self { out }
+        Self {
+            out,
+            bits: Bits::Bits64,
+            features: HashSet::new(),
+            labels: 0,
+            bound: HashSet::new(),
+            defined: HashSet::new(),
+            duplicates: Vec::new(),
+            referenced: HashSet::new(),
+            externs: HashSet::new(),
+            locals: HashSet::new(),
+            scope: None,
+            function: None,
+        }
+    }
+
+    pub fn enable(&mut self, feature: Feature) {
+        self.features.insert(feature);
+    }
+    pub fn disable(&mut self, feature: Feature) {
+        self.features.remove(&feature);
     }
 
     fn emit_triop<'a, 'b, 'c>(
@@ -20,17 +60,23 @@ impl<O: Write> NasmWriter<O> {
         rd: impl Into<Operand<'a>>,
         rs0: impl Into<Operand<'b>>,
         rs1: impl Into<Operand<'c>>,
-    ) -> io::Result<()> {
-        let rd = rd.into();
-        let rs0 = rs0.into();
-        let rs1 = rs1.into();
+    ) -> Result<()> {
+        let rd = self.validate_mem(name, rd.into())?;
+        let rs0 = self.validate_mem(name, rs0.into())?;
+        let rs1 = self.validate_mem(name, rs1.into())?;
+        let mut ops = [rd, rs0, rs1];
+        self.infer_sizes(name, &mut ops)?;
+        let [rd, rs0, rs1] = ops;
+        self.check_operands(name, &[&rd, &rs0, &rs1])?;
+        let rs0 = fold_imm(&rd, rs0);
+        let rs1 = fold_imm(&rd, rs1);
 
         write!(self.out, "    {name} ")?;
-        self.print_operand(&rd)?;
+        self.print_operand(name, &rd)?;
         write!(self.out, ", ")?;
-        self.print_operand(&rs0)?;
+        self.print_operand(name, &rs0)?;
         write!(self.out, ", ")?;
-        self.print_operand(&rs1)?;
+        self.print_operand(name, &rs1)?;
         writeln!(self.out)?;
 
         Ok(())
@@ -40,14 +86,19 @@ impl<O: Write> NasmWriter<O> {
         name: &str,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> io::Result<()> {
-        let rd = rd.into();
-        let rs = rs.into();
+    ) -> Result<()> {
+        let rd = self.validate_mem(name, rd.into())?;
+        let rs = self.validate_mem(name, rs.into())?;
+        let mut ops = [rd, rs];
+        self.infer_sizes(name, &mut ops)?;
+        let [rd, rs] = ops;
+        self.check_operands(name, &[&rd, &rs])?;
+        let rs = fold_imm(&rd, rs);
 
         write!(self.out, "    {name} ")?;
-        self.print_operand(&rd)?;
+        self.print_operand(name, &rd)?;
         write!(self.out, ", ")?;
-        self.print_operand(&rs)?;
+        self.print_operand(name, &rs)?;
         writeln!(self.out)?;
 
         Ok(())
@@ -58,25 +109,33 @@ impl<O: Write> NasmWriter<O> {
         cc: Condition,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> io::Result<()> {
-        let rd = rd.into();
-        let rs = rs.into();
+    ) -> Result<()> {
+        let rd = self.validate_mem(name, rd.into())?;
+        let rs = self.validate_mem(name, rs.into())?;
+        let mut ops = [rd, rs];
+        self.infer_sizes(name, &mut ops)?;
+        let [rd, rs] = ops;
+        self.check_operands(name, &[&rd, &rs])?;
 
         write!(self.out, "    {name}")?;
         self.print_cc(cc)?;
         write!(self.out, " ")?;
-        self.print_operand(&rd)?;
+        self.print_operand(name, &rd)?;
         write!(self.out, ", ")?;
-        self.print_operand(&rs)?;
+        self.print_operand(name, &rs)?;
         writeln!(self.out)?;
 
         Ok(())
     }
-    fn emit_unop<'a>(&mut self, name: &str, r: impl Into<Operand<'a>>) -> io::Result<()> {
-        let r = r.into();
+    fn emit_unop<'a>(&mut self, name: &str, r: impl Into<Operand<'a>>) -> Result<()> {
+        let r = self.validate_mem(name, r.into())?;
+        let mut ops = [r];
+        self.infer_sizes(name, &mut ops)?;
+        let [r] = ops;
+        self.check_operands(name, &[&r])?;
 
         write!(self.out, "    {name} ")?;
-        self.print_operand(&r)?;
+        self.print_operand(name, &r)?;
         writeln!(self.out)?;
 
         Ok(())
@@ -86,23 +145,535 @@ impl<O: Write> NasmWriter<O> {
         name: &str,
         cc: Condition,
         r: impl Into<Operand<'a>>,
-    ) -> io::Result<()> {
-        let r = r.into();
+    ) -> Result<()> {
+        let r = self.validate_mem(name, r.into())?;
+        let mut ops = [r];
+        self.infer_sizes(name, &mut ops)?;
+        let [r] = ops;
+        self.check_operands(name, &[&r])?;
 
         write!(self.out, "    {name}")?;
         self.print_cc(cc)?;
         write!(self.out, " ")?;
-        self.print_operand(&r)?;
+        self.print_operand(name, &r)?;
+        writeln!(self.out)?;
+
+        Ok(())
+    }
+    fn emit_far<'a>(&mut self, name: &str, r: impl Into<Operand<'a>>) -> Result<()> {
+        let r = self.validate_mem(name, r.into())?;
+        let mut ops = [r];
+        self.infer_sizes(name, &mut ops)?;
+        let [r] = ops;
+        self.check_operands(name, &[&r])?;
+        match r {
+            Operand::Far(_) if self.bits == Bits::Bits64 => {
+                return Err(Error::invalid(name, &[&r], InvalidKind::NotInMode(self.bits)));
+            }
+            Operand::Far(_) | Operand::Mem(_) => (),
+            _ => return Err(Error::invalid(name, &[&r], InvalidKind::IllegalOperand)),
+        }
+
+        write!(self.out, "    {name} ")?;
+        if let Operand::Mem(_) = r {
+            write!(self.out, "far ")?;
+        }
+        self.print_operand(name, &r)?;
+        writeln!(self.out)?;
+
+        Ok(())
+    }
+    fn emit_indirect<'a>(&mut self, name: &str, r: impl Into<Operand<'a>>) -> Result<()> {
+        let r = r.into();
+        match r {
+            Operand::Reg(reg) if self.features.contains(&Feature::Retpoline) => {
+                check_qword(name, reg)?;
+                let thunk = mitigation::thunk_name(reg)?;
+                self.emit_unop(name, thunk.as_str())
+            }
+            _ => self.emit_unop(name, r),
+        }
+    }
+    fn emit_data(&mut self, name: &str, bits: u32, label: &str, values: &[Data]) -> Result<()> {
+        for value in values {
+            match value {
+                &Data::Integer(value) if bits > 64 => {
+                    let op = Operand::Integer(value);
+                    return Err(Error::invalid(name, &[&op], InvalidKind::IllegalOperand));
+                }
+                Data::Expr(expr) if bits > 64 => {
+                    let op = Operand::Expr(expr);
+                    return Err(Error::invalid(name, &[&op], InvalidKind::IllegalOperand));
+                }
+                &Data::Integer(value) if !(-(1 << (bits - 1))..(1 << bits)).contains(&value) => {
+                    let op = Operand::Integer(value);
+                    return Err(Error::invalid(name, &[&op], InvalidKind::ImmediateTooWide));
+                }
+                _ => (),
+            }
+        }
+
+        if !label.is_empty() {
+            self.define(label);
+        }
+        write!(self.out, "{label} {name} ")?;
+        for (i, value) in values.iter().enumerate() {
+            if i != 0 {
+                write!(self.out, ", ")?;
+            }
+            match value {
+                Data::Integer(value) => write!(self.out, "{value}")?,
+                &Data::Float(value) => self.print_float(value)?,
+                Data::Expr(expr) => self.print_expr(expr)?,
+            }
+        }
         writeln!(self.out)?;
 
         Ok(())
     }
-    fn emit_nulop(&mut self, name: &str) -> io::Result<()> {
+    fn emit_nulop(&mut self, name: &str) -> Result<()> {
+        self.check_mode(name, &[])?;
+        if name == "into" && self.bits == Bits::Bits64 {
+            return Err(Error::invalid(name, &[], InvalidKind::NotInMode(self.bits)));
+        }
         writeln!(self.out, "    {name}")?;
         Ok(())
     }
 
-    fn print_cc(&mut self, cc: Condition) -> io::Result<()> {
+    fn check_cc(&self, name: &str, cc: Condition) -> Result<()> {
+        if cc.is_count_test() {
+            return Err(Error::invalid(name, &[], InvalidKind::IllegalCondition(cc)));
+        }
+
+        Ok(())
+    }
+
+    fn validate_mem<'a>(&self, name: &str, op: Operand<'a>) -> Result<Operand<'a>> {
+        let Operand::Mem(mem) = op else {
+            return Ok(op);
+        };
+        match mem.validate(self.bits) {
+            Ok(mem) => Ok(Operand::Mem(mem)),
+            Err(err) => Err(Error::invalid(name, &[&op], InvalidKind::Mem(err))),
+        }
+    }
+    fn infer_sizes(&self, name: &str, ops: &mut [Operand]) -> Result<()> {
+        let unsized_mem = ops
+            .iter()
+            .position(|op| matches!(op, Operand::Mem(mem) if mem.size.is_none()));
+        let Some(i) = unsized_mem else {
+            return Ok(());
+        };
+
+        let size = if is_same_size(name) {
+            ops.iter().find_map(|op| match op {
+                Operand::Mem(_)
+                | Operand::Integer(_)
+                | Operand::Label(_)
+                | Operand::Expr(_) => None,
+                op => op.size(),
+            })
+        } else if name == "set" {
+            Some(OSize::Byte)
+        } else if matches!(name, "push" | "pop") {
+            Some(self.stack_size())
+        } else if is_shift(name) || is_rm_only(name) || matches!(name, "movsx" | "movzx") {
+            None
+        } else {
+            let imm = ops
+                .iter()
+                .any(|op| matches!(op, Operand::Integer(_) | Operand::Expr(_)));
+            let sized = ops.iter().any(|op| op.size().is_some());
+            if !imm || sized {
+                return Ok(());
+            }
+            None
+        };
+
+        let Some(size) = size else {
+            let refs: Vec<&Operand> = ops.iter().collect();
+            return Err(Error::invalid(name, &refs, InvalidKind::AmbiguousSize));
+        };
+        if let Operand::Mem(mem) = &mut ops[i] {
+            mem.size = Some(size);
+        }
+
+        Ok(())
+    }
+    fn check_operands(&self, name: &str, ops: &[&Operand]) -> Result<()> {
+        self.check_high_byte(name, ops)?;
+        self.check_addressing(name, ops)?;
+        self.check_mode(name, ops)?;
+        self.check_apx_regs(name, ops)?;
+        self.check_ndd(name, ops)?;
+        self.check_branch(name, ops)?;
+        self.check_shape(name, ops)?;
+
+        Ok(())
+    }
+    fn check_branch(&self, name: &str, ops: &[&Operand]) -> Result<()> {
+        for op in ops {
+            let Operand::Branch(branch) = op else {
+                continue;
+            };
+            let legal = match branch.distance {
+                Distance::Short => matches!(name, "j" | "jmp" | "loop" | "loope" | "loopne"),
+                Distance::Near => matches!(name, "j" | "jmp" | "call"),
+            };
+            if !legal {
+                return Err(Error::invalid(name, ops, InvalidKind::IllegalOperand));
+            }
+        }
+
+        Ok(())
+    }
+    fn check_shape(&self, name: &str, ops: &[&Operand]) -> Result<()> {
+        let mems = ops
+            .iter()
+            .filter(|op| matches!(op, Operand::Mem(_)))
+            .count();
+        if mems > 1 {
+            return Err(Error::invalid(name, ops, InvalidKind::MemoryToMemory));
+        }
+
+        let same_size = is_same_size(name);
+        let shift = is_shift(name);
+        let rm_only = is_rm_only(name);
+        if !same_size && !shift && !rm_only && name != "push" {
+            return Ok(());
+        }
+
+        let Some(dst) = ops.first() else {
+            return Ok(());
+        };
+        let reg_only = name == "cmov" || (name == "imul" && ops.len() > 1);
+        if reg_only && !matches!(dst, Operand::Reg(_)) {
+            return Err(Error::invalid(name, ops, InvalidKind::IllegalOperand));
+        }
+        let dst_ok = match dst {
+            Operand::Reg(_) | Operand::Mem(_) => true,
+            Operand::Integer(_) | Operand::Label(_) | Operand::Expr(_) => name == "push",
+            Operand::SReg(_) => matches!(name, "mov" | "pop" | "push"),
+            Operand::CReg(_) | Operand::DReg(_) => name == "mov",
+            _ => false,
+        };
+        if !dst_ok {
+            return Err(Error::invalid(name, ops, InvalidKind::IllegalOperand));
+        }
+
+        if shift {
+            return match ops.get(1) {
+                None | Some(Operand::Reg(CL)) => Ok(()),
+                Some(Operand::Integer(0..=255)) => Ok(()),
+                Some(Operand::Integer(_)) => {
+                    Err(Error::invalid(name, ops, InvalidKind::ImmediateTooWide))
+                }
+                Some(_) => Err(Error::invalid(name, ops, InvalidKind::IllegalOperand)),
+            };
+        }
+
+        if name == "push" {
+            return match dst {
+                Operand::Integer(value) if !imm_fits(*value, self.stack_size(), false) => {
+                    Err(Error::invalid(name, ops, InvalidKind::ImmediateTooWide))
+                }
+                _ => Ok(()),
+            };
+        }
+
+        let system = ops
+            .iter()
+            .any(|op| matches!(op, Operand::SReg(_) | Operand::CReg(_) | Operand::DReg(_)));
+        if same_size
+            && !system
+            && let Some(dst_size) = dst.size()
+        {
+            let mut sizes = ops.iter().filter_map(|op| match op {
+                Operand::Integer(_) | Operand::Label(_) | Operand::Expr(_) => None,
+                op => op.size(),
+            });
+            if sizes.any(|size| size != dst_size) {
+                return Err(Error::invalid(name, ops, InvalidKind::SizeMismatch));
+            }
+            let imm64 = name == "mov" && matches!(dst, Operand::Reg(_));
+            let too_wide = ops.iter().any(
+                |op| matches!(op, Operand::Integer(value) if !imm_fits(*value, dst_size, imm64)),
+            );
+            if too_wide {
+                return Err(Error::invalid(name, ops, InvalidKind::ImmediateTooWide));
+            }
+        }
+
+        Ok(())
+    }
+    fn stack_size(&self) -> OSize {
+        match self.bits {
+            Bits::Bits16 => OSize::Word,
+            Bits::Bits32 => OSize::DWord,
+            Bits::Bits64 => OSize::QWord,
+        }
+    }
+    fn check_feature(&self, name: &str, feature: Feature) -> Result<()> {
+        if !self.features.contains(&feature) {
+            let kind = InvalidKind::MissingFeature(feature);
+            return Err(Error::invalid(name, &[], kind));
+        }
+
+        Ok(())
+    }
+    fn check_apx_regs(&self, name: &str, ops: &[&Operand]) -> Result<()> {
+        let uses_apx = ops.iter().any(|op| match op {
+            Operand::Reg(r) => r.name.is_apx(),
+            Operand::Mem(mem) => {
+                let base = mem.base.is_some_and(|r| r.name.is_apx());
+                let index = mem.index.is_some_and(|(r, _)| r.name.is_apx());
+                base || index
+            }
+            _ => false,
+        });
+        if uses_apx {
+            self.check_feature(name, Feature::Apx)?;
+        }
+
+        Ok(())
+    }
+    fn check_ndd(&self, name: &str, ops: &[&Operand]) -> Result<()> {
+        let ndd = match ops.len() {
+            3 => is_same_size(name) && name != "imul",
+            2 => matches!(name, "dec" | "inc" | "neg" | "not"),
+            _ => false,
+        };
+        if !ndd {
+            return Ok(());
+        }
+
+        if self.bits != Bits::Bits64 {
+            return Err(Error::invalid(name, ops, InvalidKind::NotInMode(self.bits)));
+        }
+        if !matches!(ops[0], Operand::Reg(_)) {
+            return Err(Error::invalid(name, ops, InvalidKind::IllegalOperand));
+        }
+
+        Ok(())
+    }
+    fn check_pair(&self, name: &str, r0: Reg, r1: Reg) -> Result<()> {
+        self.check_feature(name, Feature::Apx)?;
+        let valid = r0.size == RSize::QWord
+            && r1.size == RSize::QWord
+            && r0.name != RName::SP
+            && r1.name != RName::SP
+            && r0.name != r1.name;
+        if !valid {
+            let ops = [&Operand::Reg(r0), &Operand::Reg(r1)];
+            return Err(Error::invalid(name, &ops, InvalidKind::IllegalPair));
+        }
+
+        Ok(())
+    }
+    fn check_mode(&self, name: &str, ops: &[&Operand]) -> Result<()> {
+        if self.bits != Bits::Bits64 && is_long_only(name) {
+            return Err(Error::invalid(name, ops, InvalidKind::NotInMode(self.bits)));
+        }
+        for op in ops {
+            let legal = match (self.bits, op) {
+                (Bits::Bits64, _) => true,
+                (_, Operand::CReg(CReg::CR8)) => false,
+                (_, Operand::Mem(mem)) => {
+                    let long_addressing =
+                        matches!(mem.addressing, Some(Addressing::Rel | Addressing::Abs64));
+                    !long_addressing && !op.needs_rex()
+                }
+                _ => !op.needs_rex(),
+            };
+            if !legal {
+                return Err(Error::invalid(name, ops, InvalidKind::NotInMode(self.bits)));
+            }
+        }
+
+        Ok(())
+    }
+    fn check_addressing(&self, name: &str, ops: &[&Operand]) -> Result<()> {
+        for op in ops {
+            let Operand::Mem(mem) = op else { continue };
+            let Some(addressing) = mem.addressing else {
+                continue;
+            };
+
+            let has_regs = mem.base.is_some() || mem.index.is_some();
+            if addressing != Addressing::Abs32 && has_regs {
+                let kind = InvalidKind::AddressingWithRegisters(addressing);
+                return Err(Error::invalid(name, ops, kind));
+            }
+            if addressing == Addressing::Abs64 {
+                let accumulator = ops.iter().any(
+                    |op| matches!(op, Operand::Reg(r) if r.name == RName::A && !r.is_high_byte()),
+                );
+                if name != "mov" || ops.len() != 2 || !accumulator {
+                    return Err(Error::invalid(name, ops, InvalidKind::Abs64NeedsAccumulator));
+                }
+            }
+        }
+
+        Ok(())
+    }
+    fn check_high_byte(&self, name: &str, ops: &[&Operand]) -> Result<()> {
+        for op in ops {
+            if let Operand::Reg(reg) = op
+                && reg.is_high_byte()
+                && !matches!(reg.name, RName::A | RName::B | RName::C | RName::D)
+            {
+                return Err(Error::invalid(name, ops, InvalidKind::NoHighByte));
+            }
+        }
+        let high = ops.iter().any(|op| op.is_high_byte());
+        let rex = ops.iter().any(|op| op.needs_rex());
+        if high && rex {
+            return Err(Error::invalid(name, ops, InvalidKind::HighByteConflict));
+        }
+
+        Ok(())
+    }
+    fn check_mov_sreg(&self, rd: &Operand, rs: &Operand) -> Result<()> {
+        let valid = match (rd, rs) {
+            (Operand::SReg(SReg::CS), _) => false,
+            (Operand::SReg(_), Operand::Reg(reg)) | (Operand::Reg(reg), Operand::SReg(_)) => {
+                matches!(reg.size, RSize::Word | RSize::DWord | RSize::QWord)
+            }
+            (Operand::SReg(_), Operand::Mem(mem)) | (Operand::Mem(mem), Operand::SReg(_)) => {
+                matches!(mem.size, None | Some(OSize::Word))
+            }
+            (Operand::SReg(_), _) | (_, Operand::SReg(_)) => false,
+            _ => true,
+        };
+        if !valid {
+            let ops = [rd, rs];
+            return Err(Error::invalid("mov", &ops, InvalidKind::NoSegmentForm));
+        }
+
+        Ok(())
+    }
+    fn check_mov_sys(&self, rd: &Operand, rs: &Operand) -> Result<()> {
+        let native = match self.bits {
+            Bits::Bits64 => RSize::QWord,
+            _ => RSize::DWord,
+        };
+        let valid = match (rd, rs) {
+            (Operand::CReg(_) | Operand::DReg(_), Operand::Reg(reg))
+            | (Operand::Reg(reg), Operand::CReg(_) | Operand::DReg(_)) => reg.size == native,
+            (Operand::CReg(_) | Operand::DReg(_), _) | (_, Operand::CReg(_) | Operand::DReg(_)) => {
+                false
+            }
+            _ => true,
+        };
+        if !valid {
+            let ops = [rd, rs];
+            return Err(Error::invalid("mov", &ops, InvalidKind::IllegalOperand));
+        }
+
+        Ok(())
+    }
+    fn check_port(&self, name: &str, reg: Reg, port: &Operand) -> Result<()> {
+        let ops = [&Operand::Reg(reg), port];
+        if reg.name != RName::A || matches!(reg.size, RSize::HighByte | RSize::QWord) {
+            return Err(Error::invalid(name, &ops, InvalidKind::AccumulatorOnly));
+        }
+        match *port {
+            Operand::Reg(DX) => Ok(()),
+            Operand::Integer(0..=255) => Ok(()),
+            Operand::Integer(_) => Err(Error::invalid(name, &ops, InvalidKind::ImmediateTooWide)),
+            _ => Err(Error::invalid(name, &ops, InvalidKind::IllegalPort)),
+        }
+    }
+
+    fn check_not_byte(&self, name: &str, reg: Reg) -> Result<()> {
+        if matches!(reg.size, RSize::Byte | RSize::HighByte) {
+            let ops = [&Operand::Reg(reg)];
+            return Err(Error::invalid(name, &ops, InvalidKind::NoByteForm));
+        }
+
+        Ok(())
+    }
+
+    fn check_sse(&self, name: &str, rd: VReg, rs: &Operand) -> Result<()> {
+        let rs_ok = match rs {
+            Operand::VReg(r) => r.size == VSize::Xmm,
+            Operand::Mem(_) => true,
+            _ => false,
+        };
+        if rd.size != VSize::Xmm || !rs_ok {
+            let ops = [&Operand::VReg(rd), rs];
+            return Err(Error::invalid(name, &ops, InvalidKind::NeedsXmm));
+        }
+
+        Ok(())
+    }
+    fn emit_sse<'a>(&mut self, name: &str, rd: VReg, rs: impl Into<Operand<'a>>) -> Result<()> {
+        let rs = rs.into();
+        self.check_sse(name, rd, &rs)?;
+        self.emit_binop(name, rd, rs)
+    }
+    fn emit_sse_imm<'a>(
+        &mut self,
+        name: &str,
+        rd: VReg,
+        rs: impl Into<Operand<'a>>,
+        imm: u8,
+    ) -> Result<()> {
+        let rs = rs.into();
+        self.check_sse(name, rd, &rs)?;
+        self.emit_triop(name, rd, rs, imm)
+    }
+
+    fn emit_fma<'a>(
+        &mut self,
+        name: &str,
+        ty: FpType,
+        rd: VReg,
+        rs0: VReg,
+        rs1: impl Into<Operand<'a>>,
+    ) -> Result<()> {
+        let rs1 = self.validate_mem(name, rs1.into())?;
+        let packed_only = name.starts_with("vfmaddsub") || name.starts_with("vfmsubadd");
+        let rs1_ok = match rs1 {
+            Operand::VReg(r) => r.size == rd.size,
+            Operand::Mem(_) => true,
+            _ => false,
+        };
+        let valid = rs0.size == rd.size
+            && rs1_ok
+            && (ty.is_packed() || rd.size == VSize::Xmm)
+            && (ty.is_packed() || !packed_only);
+        if !valid {
+            let ops = [&Operand::VReg(rd), &Operand::VReg(rs0), &rs1];
+            return Err(Error::invalid(name, &ops, InvalidKind::IllegalFpType(ty)));
+        }
+        self.check_operands(name, &[&Operand::VReg(rd), &Operand::VReg(rs0), &rs1])?;
+
+        write!(self.out, "    {name}")?;
+        self.print_fp_type(ty)?;
+        write!(self.out, " ")?;
+        self.print_vreg(rd)?;
+        write!(self.out, ", ")?;
+        self.print_vreg(rs0)?;
+        write!(self.out, ", ")?;
+        self.print_operand(name, &rs1)?;
+        writeln!(self.out)?;
+
+        Ok(())
+    }
+
+    fn print_fp_type(&mut self, ty: FpType) -> Result<()> {
+        let name = match ty {
+            FpType::PS => "ps",
+            FpType::PD => "pd",
+            FpType::SS => "ss",
+            FpType::SD => "sd",
+        };
+        write!(self.out, "{name}")?;
+
+        Ok(())
+    }
+    fn print_cc(&mut self, cc: Condition) -> Result<()> {
         let name = match cc {
             Condition::A => "a",
             Condition::AE => "ae",
@@ -142,16 +713,115 @@ impl<O: Write> NasmWriter<O> {
 
         Ok(())
     }
-    fn print_operand(&mut self, op: &Operand) -> io::Result<()> {
+    fn qualify(&self, label: &str) -> String {
+        match &self.scope {
+            Some(scope) if is_local(label) => format!("{scope}{label}"),
+            _ => label.to_string(),
+        }
+    }
+    fn define(&mut self, label: &str) {
+        let qualified = self.qualify(label);
+        if is_local(label) {
+            self.locals.insert(qualified.clone());
+        } else if !label.starts_with("..") {
+            self.scope = Some(label.to_string());
+        }
+        if !self.defined.insert(qualified.clone()) {
+            self.duplicates.push(qualified);
+        }
+    }
+    fn reference(&mut self, label: &str) {
+        let qualified = self.qualify(label);
+        self.referenced.insert(qualified);
+    }
+
+    fn print_operand(&mut self, name: &str, op: &Operand) -> Result<()> {
         match op {
-            &Operand::Reg(reg) => self.print_reg(reg),
-            Operand::Mem(mem) => self.print_mem(mem),
-            &Operand::Integer(value) => write!(self.out, "{value}"),
-            &Operand::Label(label) => write!(self.out, "{label}"),
+            &Operand::Reg(reg) => self.print_reg(name, reg),
+            &Operand::SReg(reg) => self.print_sreg(reg),
+            &Operand::CReg(reg) => self.print_creg(reg),
+            &Operand::DReg(reg) => self.print_dreg(reg),
+            &Operand::VReg(reg) => self.print_vreg(reg),
+            Operand::Mem(mem) => self.print_mem(name, mem),
+            &Operand::Integer(value) => Ok(write!(self.out, "{value}")?),
+            &Operand::Label(label) => {
+                self.reference(label);
+                Ok(write!(self.out, "{label}")?)
+            }
+            Operand::Expr(expr) => self.print_expr(expr),
+            Operand::Far(far) => self.print_far(far),
+            Operand::Branch(branch) => self.print_branch(branch),
+        }
+    }
+
+    fn print_branch(&mut self, branch: &Branch) -> Result<()> {
+        let distance = match branch.distance {
+            Distance::Short => "short",
+            Distance::Near => "near",
+        };
+        self.reference(branch.target);
+        write!(self.out, "{distance} {}", branch.target)?;
+
+        Ok(())
+    }
+
+    fn print_float(&mut self, value: f64) -> Result<()> {
+        if value.is_nan() {
+            write!(self.out, "__QNaN__")?;
+        } else if value.is_infinite() {
+            let sign = if value < 0.0 { "-" } else { "" };
+            write!(self.out, "{sign}__Infinity__")?;
+        } else {
+            let digits = format!("{value:?}");
+            match digits.split_once('e') {
+                Some((mantissa, exp)) if !mantissa.contains('.') => {
+                    write!(self.out, "{mantissa}.0e{exp}")?
+                }
+                _ => write!(self.out, "{digits}")?,
+            }
+        }
+
+        Ok(())
+    }
+    fn print_expr(&mut self, expr: &Expr) -> Result<()> {
+        match expr {
+            Expr::Integer(value) => write!(self.out, "{value}")?,
+            &Expr::Symbol(name) => {
+                self.reference(name);
+                write!(self.out, "{name}")?
+            }
+            Expr::Here => write!(self.out, "$")?,
+            Expr::SectionStart => write!(self.out, "$$")?,
+            Expr::Binary(op, lhs, rhs) => {
+                write!(self.out, "(")?;
+                self.print_expr(lhs)?;
+                write!(self.out, " {} ", op.symbol())?;
+                self.print_expr(rhs)?;
+                write!(self.out, ")")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn print_far(&mut self, far: &FarPtr) -> Result<()> {
+        write!(self.out, "{}:", far.segment)?;
+        if let Some(label) = far.label {
+            self.reference(label);
         }
+        match far.label {
+            Some(label) if far.offset < 0 => {
+                write!(self.out, "{label} - {}", far.offset.unsigned_abs())?
+            }
+            Some(label) if far.offset > 0 => write!(self.out, "{label} + {}", far.offset)?,
+            Some(label) => write!(self.out, "{label}")?,
+            None => write!(self.out, "{}", far.offset)?,
+        }
+
+        Ok(())
     }
 
-    fn print_mem(&mut self, mem: &Mem) -> io::Result<()> {
+    fn print_mem(&mut self, name: &str, mem: &Mem) -> Result<()> {
         let mut needs_plus = false;
 
         if let Some(size) = mem.size {
@@ -159,7 +829,18 @@ impl<O: Write> NasmWriter<O> {
         }
 
         write!(self.out, "[")?;
+        match mem.addressing {
+            Some(Addressing::Rel) => write!(self.out, "rel ")?,
+            Some(Addressing::Abs32) => write!(self.out, "abs ")?,
+            Some(Addressing::Abs64) => write!(self.out, "abs qword ")?,
+            None => (),
+        }
+        if let Some(segment) = mem.segment {
+            self.print_sreg(segment)?;
+            write!(self.out, ":")?;
+        }
         if let Some(label) = mem.label {
+            self.reference(label);
             needs_plus = true;
             write!(self.out, "{label}")?;
         }
@@ -169,7 +850,7 @@ impl<O: Write> NasmWriter<O> {
                 write!(self.out, " + ")?;
             }
             needs_plus = true;
-            self.print_reg(base)?;
+            self.print_reg(name, base)?;
         }
 
         if let Some((index, scale)) = mem.index {
@@ -178,7 +859,7 @@ impl<O: Write> NasmWriter<O> {
             }
             needs_plus = true;
 
-            self.print_reg(index)?;
+            self.print_reg(name, index)?;
             match scale {
                 Scale::One => (),
                 Scale::Two => write!(self.out, " * 2")?,
@@ -187,7 +868,15 @@ impl<O: Write> NasmWriter<O> {
             }
         }
 
-        if mem.offset != 0 {
+        if let Some(expr) = mem.expr {
+            if needs_plus {
+                write!(self.out, " + ")?;
+            }
+            needs_plus = true;
+            self.print_expr(expr)?;
+        }
+
+        if mem.offset != 0 || !needs_plus {
             let is_neg = mem.offset < 0;
             if needs_plus && is_neg {
                 write!(self.out, " - {}", mem.offset.unsigned_abs())?;
@@ -202,21 +891,25 @@ impl<O: Write> NasmWriter<O> {
 
         Ok(())
     }
-    fn print_reg(&mut self, reg: Reg) -> io::Result<()> {
+    fn print_reg(&mut self, mnemonic: &str, reg: Reg) -> Result<()> {
         let name = match reg {
             AL => "al",
+            AH => "ah",
             AX => "ax",
             EAX => "eax",
             RAX => "rax",
             BL => "bl",
+            BH => "bh",
             BX => "bx",
             EBX => "ebx",
             RBX => "rbx",
             CL => "cl",
+            CH => "ch",
             CX => "cx",
             ECX => "ecx",
             RCX => "rcx",
             DL => "dl",
+            DH => "dh",
             DX => "dx",
             EDX => "edx",
             RDX => "rdx",
@@ -268,17 +961,136 @@ impl<O: Write> NasmWriter<O> {
             R15W => "r15w",
             R15D => "r15d",
             R15 =>  "r15",
+            R16B => "r16b",
+            R16W => "r16w",
+            R16D => "r16d",
+            R16 => "r16",
+            R17B => "r17b",
+            R17W => "r17w",
+            R17D => "r17d",
+            R17 => "r17",
+            R18B => "r18b",
+            R18W => "r18w",
+            R18D => "r18d",
+            R18 => "r18",
+            R19B => "r19b",
+            R19W => "r19w",
+            R19D => "r19d",
+            R19 => "r19",
+            R20B => "r20b",
+            R20W => "r20w",
+            R20D => "r20d",
+            R20 => "r20",
+            R21B => "r21b",
+            R21W => "r21w",
+            R21D => "r21d",
+            R21 => "r21",
+            R22B => "r22b",
+            R22W => "r22w",
+            R22D => "r22d",
+            R22 => "r22",
+            R23B => "r23b",
+            R23W => "r23w",
+            R23D => "r23d",
+            R23 => "r23",
+            R24B => "r24b",
+            R24W => "r24w",
+            R24D => "r24d",
+            R24 => "r24",
+            R25B => "r25b",
+            R25W => "r25w",
+            R25D => "r25d",
+            R25 => "r25",
+            R26B => "r26b",
+            R26W => "r26w",
+            R26D => "r26d",
+            R26 => "r26",
+            R27B => "r27b",
+            R27W => "r27w",
+            R27D => "r27d",
+            R27 => "r27",
+            R28B => "r28b",
+            R28W => "r28w",
+            R28D => "r28d",
+            R28 => "r28",
+            R29B => "r29b",
+            R29W => "r29w",
+            R29D => "r29d",
+            R29 => "r29",
+            R30B => "r30b",
+            R30W => "r30w",
+            R30D => "r30d",
+            R30 => "r30",
+            R31B => "r31b",
+            R31W => "r31w",
+            R31D => "r31d",
+            R31 => "r31",
+            _ => {
+                let ops = [&Operand::Reg(reg)];
+                return Err(Error::invalid(mnemonic, &ops, InvalidKind::NoHighByte));
+            }
+        };
+
+        write!(self.out, "{name}")?;
+        Ok(())
+    }
+    fn print_sreg(&mut self, reg: SReg) -> Result<()> {
+        let name = match reg {
+            SReg::ES => "es",
+            SReg::CS => "cs",
+            SReg::SS => "ss",
+            SReg::DS => "ds",
+            SReg::FS => "fs",
+            SReg::GS => "gs",
+        };
+
+        write!(self.out, "{name}")?;
+        Ok(())
+    }
+    fn print_creg(&mut self, reg: CReg) -> Result<()> {
+        let name = match reg {
+            CReg::CR0 => "cr0",
+            CReg::CR2 => "cr2",
+            CReg::CR3 => "cr3",
+            CReg::CR4 => "cr4",
+            CReg::CR8 => "cr8",
+        };
+
+        write!(self.out, "{name}")?;
+        Ok(())
+    }
+    fn print_dreg(&mut self, reg: DReg) -> Result<()> {
+        let name = match reg {
+            DReg::DR0 => "dr0",
+            DReg::DR1 => "dr1",
+            DReg::DR2 => "dr2",
+            DReg::DR3 => "dr3",
+            DReg::DR4 => "dr4",
+            DReg::DR5 => "dr5",
+            DReg::DR6 => "dr6",
+            DReg::DR7 => "dr7",
         };
 
         write!(self.out, "{name}")?;
         Ok(())
     }
-    fn print_size(&mut self, size: OSize) -> io::Result<()> {
+    fn print_vreg(&mut self, reg: VReg) -> Result<()> {
+        let prefix = match reg.size {
+            VSize::Xmm => "xmm",
+            VSize::Ymm => "ymm",
+        };
+
+        write!(self.out, "{prefix}{}", reg.name as u8)?;
+        Ok(())
+    }
+    fn print_size(&mut self, size: OSize) -> Result<()> {
         let name = match size {
             OSize::Byte => "byte",
             OSize::Word => "word",
             OSize::DWord => "dword",
             OSize::QWord => "qword",
+            OSize::OWord => "oword",
+            OSize::YWord => "yword",
         };
 
         write!(self.out, "{name}")?;
@@ -286,48 +1098,182 @@ impl<O: Write> NasmWriter<O> {
     }
 }
 impl<O: Write> X86Writer for NasmWriter<O> {
-    fn emit_preamble(&mut self) -> std::io::Result<()> {
-        writeln!(self.out, "default rel")?;
+    fn emit_preamble(&mut self) -> Result<()> {
+        if self.bits == Bits::Bits64 {
+            writeln!(self.out, "default rel")?;
+        }
 
         Ok(())
     }
 
-    fn label(&mut self, label: &str) -> std::io::Result<()> {
-        writeln!(self.out, "{label}:")?;
+    fn bits(&mut self, bits: Bits) -> Result<()> {
+        let n = match bits {
+            Bits::Bits16 => 16,
+            Bits::Bits32 => 32,
+            Bits::Bits64 => 64,
+        };
+        writeln!(self.out, "bits {n}")?;
+        self.bits = bits;
 
         Ok(())
     }
 
-    fn global(&mut self, label: &str) -> std::io::Result<()> {
-        writeln!(self.out, "global {label}")?;
+    fn label(&mut self, label: &str) -> Result<()> {
+        self.define(label);
+        writeln!(self.out, "{label}:")?;
 
         Ok(())
     }
 
-    fn text(&mut self) -> std::io::Result<()> {
-        writeln!(self.out, "section .text")?;
-
-        Ok(())
+    fn new_label(&mut self) -> Label {
+        let id = self.labels;
+        self.labels += 1;
+        Label::new(id, format!("..@L{id}"))
     }
-
-    fn rodata(&mut self) -> io::Result<()> {
+    fn bind(&mut self, label: &Label) -> Result<()> {
+        if !self.bound.insert(label.id()) {
+            return Err(Error::Label(LabelError::AlreadyBound(label.name().to_string())));
+        }
+        self.label(label.name())
+    }
+    fn is_bound(&self, label: &Label) -> bool {
+        self.bound.contains(&label.id())
+    }
+    fn begin_function(&mut self, name: &str) -> Result<()> {
+        if self.function.is_some() {
+            return Err(Error::Label(LabelError::NestedFunction(name.to_string())));
+        }
+        self.function = Some(name.to_string());
+        self.label(name)
+    }
+    fn end_function(&mut self) -> Result<()> {
+        let Some(function) = self.function.take() else {
+            return Err(Error::Label(LabelError::NotInFunction));
+        };
+        self.label(&format!("{function}.end"))
+    }
+    fn new_local_label(&mut self) -> Label {
+        let id = self.labels;
+        self.labels += 1;
+        Label::new(id, format!(".L{id}"))
+    }
+
+    fn global(&mut self, label: &str) -> Result<()> {
+        writeln!(self.out, "global {label}")?;
+
+        Ok(())
+    }
+
+    fn global_symbol(&mut self, symbol: &Symbol) -> Result<()> {
+        let name = symbol.name;
+        write!(self.out, "global {name}")?;
+        let typed = symbol.kind.is_some() || symbol.visibility.is_some() || symbol.size.is_some();
+        if typed || symbol.weak {
+            let kind = match symbol.kind {
+                Some(SymbolKind::Function) => "function",
+                Some(SymbolKind::Data) => "data",
+                None => "notype",
+            };
+            write!(self.out, ":{kind}")?;
+        }
+        if symbol.weak {
+            write!(self.out, " weak")?;
+        }
+        if let Some(visibility) = symbol.visibility {
+            let visibility = match visibility {
+                Visibility::Default => "default",
+                Visibility::Internal => "internal",
+                Visibility::Hidden => "hidden",
+                Visibility::Protected => "protected",
+            };
+            write!(self.out, " {visibility}")?;
+        }
+        match symbol.size {
+            Some(SymbolSize::Bytes(size)) => write!(self.out, " {size}")?,
+            Some(SymbolSize::ToEnd) => {
+                self.reference(&format!("{name}.end"));
+                write!(self.out, " ({name}.end - {name})")?
+            }
+            None => (),
+        }
+        writeln!(self.out)?;
+
+        Ok(())
+    }
+
+    fn r#extern(&mut self, label: &str) -> Result<()> {
+        self.externs.insert(label.to_string());
+        writeln!(self.out, "extern {label}")?;
+
+        Ok(())
+    }
+
+    fn extern_weak(&mut self, label: &str) -> Result<()> {
+        self.externs.insert(label.to_string());
+        writeln!(self.out, "extern {label}:weak")?;
+
+        Ok(())
+    }
+
+    fn text(&mut self) -> Result<()> {
+        writeln!(self.out, "section .text")?;
+
+        Ok(())
+    }
+
+    fn rodata(&mut self) -> Result<()> {
         writeln!(self.out, "section .rodata")?;
         
         Ok(())
     }
 
-    fn blank(&mut self) -> io::Result<()> {
+    fn blank(&mut self) -> Result<()> {
         writeln!(self.out)?;
 
         Ok(())
     }
-    fn comment(&mut self, comment: &str) -> io::Result<()> {
+    fn comment(&mut self, comment: &str) -> Result<()> {
         writeln!(self.out, "  ; {comment}")?;
 
         Ok(())
     }
+    fn finish(&mut self) -> Result<()> {
+        let mut undefined: Vec<&String> = self
+            .referenced
+            .iter()
+            .filter(|&label| !self.defined.contains(label) && !self.externs.contains(label))
+            .collect();
+        undefined.sort();
+        let mut duplicates = self.duplicates.clone();
+        duplicates.sort();
+        duplicates.dedup();
+        let mut unused: Vec<&String> = self
+            .defined
+            .iter()
+            .filter(|&label| self.locals.contains(label) && !self.referenced.contains(label))
+            .collect();
+        unused.sort();
+
+        let errs: Vec<LabelError> = self
+            .function
+            .iter()
+            .map(|function| LabelError::UnclosedFunction(function.clone()))
+            .chain(undefined.into_iter().map(|label| LabelError::Undefined(label.clone())))
+            .chain(duplicates.into_iter().map(LabelError::Duplicate))
+            .chain(unused.into_iter().map(|label| LabelError::UnusedLocal(label.clone())))
+            .collect();
+        if !errs.is_empty() {
+            return Err(Error::Labels(errs));
+        }
+        self.out.flush()?;
+
+        Ok(())
+    }
 
-    fn db(&mut self, label: &str, bytess: &[&[u8]]) -> io::Result<()> {
+    fn db(&mut self, label: &str, bytess: &[&[u8]]) -> Result<()> {
+        if !label.is_empty() {
+            self.define(label);
+        }
         write!(self.out, "{label} db ")?;
         for (i, &bytes) in bytess.iter().enumerate() {
             let last = i == bytess.len() - 1;
@@ -353,61 +1299,168 @@ impl<O: Write> X86Writer for NasmWriter<O> {
 
         Ok(())
     }
-    fn equ(&mut self, label: &str, value: i64) -> io::Result<()> {
+    fn dw(&mut self, label: &str, values: &[Data]) -> Result<()> {
+        self.emit_data("dw", 16, label, values)
+    }
+    fn dd(&mut self, label: &str, values: &[Data]) -> Result<()> {
+        self.emit_data("dd", 32, label, values)
+    }
+    fn dq(&mut self, label: &str, values: &[Data]) -> Result<()> {
+        self.emit_data("dq", 64, label, values)
+    }
+    fn dt(&mut self, label: &str, values: &[Data]) -> Result<()> {
+        self.emit_data("dt", 80, label, values)
+    }
+    fn r#do(&mut self, label: &str, values: &[Data]) -> Result<()> {
+        self.emit_data("do", 128, label, values)
+    }
+    fn equ(&mut self, label: &str, value: i64) -> Result<()> {
+        self.define(label);
         writeln!(self.out, "{label} equ {value}")?;
 
         Ok(())
     }
+    fn equ_expr(&mut self, label: &str, value: &Expr) -> Result<()> {
+        self.define(label);
+        write!(self.out, "{label} equ ")?;
+        self.print_expr(value)?;
+        writeln!(self.out)?;
+
+        Ok(())
+    }
 
     fn adc<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> std::io::Result<()> {
+    ) -> Result<()> {
         self.emit_binop("adc", rd, rs)
     }
 
+    fn adc3<'a, 'b, 'c>(
+        &mut self,
+        rd: impl Into<Operand<'a>>,
+        rs0: impl Into<Operand<'b>>,
+        rs1: impl Into<Operand<'c>>,
+    ) -> Result<()> {
+        self.check_feature("adc", Feature::Apx)?;
+        self.emit_triop("adc", rd, rs0, rs1)
+    }
+
     fn add<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> std::io::Result<()> {
+    ) -> Result<()> {
         self.emit_binop("add", rd, rs)
     }
 
+    fn add3<'a, 'b, 'c>(
+        &mut self,
+        rd: impl Into<Operand<'a>>,
+        rs0: impl Into<Operand<'b>>,
+        rs1: impl Into<Operand<'c>>,
+    ) -> Result<()> {
+        self.check_feature("add", Feature::Apx)?;
+        self.emit_triop("add", rd, rs0, rs1)
+    }
+
+    fn aesdec<'a>(&mut self, rd: VReg, rs: impl Into<Operand<'a>>) -> Result<()> {
+        self.emit_sse("aesdec", rd, rs)
+    }
+
+    fn aesdeclast<'a>(&mut self, rd: VReg, rs: impl Into<Operand<'a>>) -> Result<()> {
+        self.emit_sse("aesdeclast", rd, rs)
+    }
+
+    fn aesenc<'a>(&mut self, rd: VReg, rs: impl Into<Operand<'a>>) -> Result<()> {
+        self.emit_sse("aesenc", rd, rs)
+    }
+
+    fn aesenclast<'a>(&mut self, rd: VReg, rs: impl Into<Operand<'a>>) -> Result<()> {
+        self.emit_sse("aesenclast", rd, rs)
+    }
+
+    fn aesimc<'a>(&mut self, rd: VReg, rs: impl Into<Operand<'a>>) -> Result<()> {
+        self.emit_sse("aesimc", rd, rs)
+    }
+
+    fn aeskeygenassist<'a>(
+        &mut self,
+        rd: VReg,
+        rs: impl Into<Operand<'a>>,
+        imm: u8,
+    ) -> Result<()> {
+        self.emit_sse_imm("aeskeygenassist", rd, rs, imm)
+    }
+
     fn and<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> std::io::Result<()> {
+    ) -> Result<()> {
         self.emit_binop("and", rd, rs)
     }
 
-    fn call<'a>(&mut self, rs: impl Into<Operand<'a>>) -> std::io::Result<()> {
-        self.emit_unop("call", rs)
+    fn and3<'a, 'b, 'c>(
+        &mut self,
+        rd: impl Into<Operand<'a>>,
+        rs0: impl Into<Operand<'b>>,
+        rs1: impl Into<Operand<'c>>,
+    ) -> Result<()> {
+        self.check_feature("and", Feature::Apx)?;
+        self.emit_triop("and", rd, rs0, rs1)
+    }
+
+    fn bt<'a, 'b>(
+        &mut self,
+        rd: impl Into<Operand<'a>>,
+        rs: impl Into<Operand<'b>>,
+    ) -> Result<()> {
+        self.emit_binop("bt", rd, rs)
+    }
+
+    fn call<'a>(&mut self, rs: impl Into<Operand<'a>>) -> Result<()> {
+        self.emit_indirect("call", rs)
+    }
+
+    fn call_far<'a>(&mut self, rs: impl Into<Operand<'a>>) -> Result<()> {
+        self.emit_far("call", rs)
     }
 
-    fn cdq(&mut self) -> std::io::Result<()> {
+    fn cdq(&mut self) -> Result<()> {
         self.emit_nulop("cdq")
     }
 
-    fn clc(&mut self) -> std::io::Result<()> {
+    fn clc(&mut self) -> Result<()> {
         self.emit_nulop("clc")
     }
 
-    fn cld(&mut self) -> std::io::Result<()> {
+    fn clflush<'a>(&mut self, rs: Mem<'a>) -> Result<()> {
+        self.emit_unop("clflush", rs)
+    }
+
+    fn clflushopt<'a>(&mut self, rs: Mem<'a>) -> Result<()> {
+        self.emit_unop("clflushopt", rs)
+    }
+
+    fn cld(&mut self) -> Result<()> {
         self.emit_nulop("cld")
     }
 
-    fn cli(&mut self) -> std::io::Result<()> {
+    fn cli(&mut self) -> Result<()> {
         self.emit_nulop("cli")
     }
 
+    fn clwb<'a>(&mut self, rs: Mem<'a>) -> Result<()> {
+        self.emit_unop("clwb", rs)
+    }
+
     fn cmp<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> std::io::Result<()> {
+    ) -> Result<()> {
         self.emit_binop("cmp", rd, rs)
     }
 
@@ -416,31 +1469,84 @@ impl<O: Write> X86Writer for NasmWriter<O> {
         cc: Condition,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> std::io::Result<()> {
+    ) -> Result<()> {
+        self.check_cc("cmov", cc)?;
         self.emit_binop_cc("cmov", cc, rd, rs)
     }
 
-    fn cqo(&mut self) -> std::io::Result<()> {
+    fn cqo(&mut self) -> Result<()> {
         self.emit_nulop("cqo")
     }
 
-    fn cwd(&mut self) -> std::io::Result<()> {
+    fn cpuid(&mut self) -> Result<()> {
+        self.emit_nulop("cpuid")
+    }
+
+    fn crc32<'a>(&mut self, rd: Reg, rs: impl Into<Operand<'a>>) -> Result<()> {
+        let rs = rs.into();
+        let valid = matches!(
+            (rd.size, rs.size()),
+            (RSize::DWord, Some(OSize::Byte | OSize::Word | OSize::DWord))
+                | (RSize::QWord, Some(OSize::Byte | OSize::QWord))
+        );
+        let source_ok = matches!(rs, Operand::Reg(_) | Operand::Mem(_));
+        if !source_ok {
+            let ops = [&Operand::Reg(rd), &rs];
+            return Err(Error::invalid("crc32", &ops, InvalidKind::IllegalOperand));
+        }
+        if rs.size().is_none() {
+            let ops = [&Operand::Reg(rd), &rs];
+            return Err(Error::invalid("crc32", &ops, InvalidKind::AmbiguousSize));
+        }
+        if !valid {
+            let ops = [&Operand::Reg(rd), &rs];
+            return Err(Error::invalid("crc32", &ops, InvalidKind::SizeMismatch));
+        }
+        self.emit_binop("crc32", rd, rs)
+    }
+
+    fn cwd(&mut self) -> Result<()> {
         self.emit_nulop("cwd")
     }
 
-    fn dec<'a>(&mut self, rd: impl Into<Operand<'a>>) -> std::io::Result<()> {
+    fn dec<'a>(&mut self, rd: impl Into<Operand<'a>>) -> Result<()> {
         self.emit_unop("dec", rd)
     }
 
-    fn div<'a>(&mut self, rs: impl Into<Operand<'a>>) -> std::io::Result<()> {
+    fn dec2<'a, 'b>(
+        &mut self,
+        rd: impl Into<Operand<'a>>,
+        rs: impl Into<Operand<'b>>,
+    ) -> Result<()> {
+        self.check_feature("dec", Feature::Apx)?;
+        self.emit_binop("dec", rd, rs)
+    }
+
+    fn div<'a>(&mut self, rs: impl Into<Operand<'a>>) -> Result<()> {
         self.emit_unop("div", rs)
     }
 
-    fn idiv<'a>(&mut self, rs: impl Into<Operand<'a>>) -> std::io::Result<()> {
+    fn endbr32(&mut self) -> Result<()> {
+        self.emit_nulop("endbr32")
+    }
+
+    fn endbr64(&mut self) -> Result<()> {
+        self.emit_nulop("endbr64")
+    }
+
+    fn enter(&mut self, alloc: u16, nesting: u8) -> Result<()> {
+        self.emit_binop("enter", alloc, nesting)
+    }
+
+    fn hlt(&mut self) -> Result<()> {
+        self.emit_nulop("hlt")
+    }
+
+    fn idiv<'a>(&mut self, rs: impl Into<Operand<'a>>) -> Result<()> {
         self.emit_unop("idiv", rs)
     }
 
-    fn imul1<'a, 'b>(&mut self, rd: impl Into<Operand<'a>>) -> std::io::Result<()> {
+    fn imul1<'a, 'b>(&mut self, rd: impl Into<Operand<'a>>) -> Result<()> {
         self.emit_unop("imul", rd)
     }
 
@@ -448,7 +1554,7 @@ impl<O: Write> X86Writer for NasmWriter<O> {
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> std::io::Result<()> {
+    ) -> Result<()> {
         self.emit_binop("imul", rd, rs)
     }
 
@@ -457,91 +1563,306 @@ impl<O: Write> X86Writer for NasmWriter<O> {
         rd: impl Into<Operand<'a>>,
         rs0: impl Into<Operand<'b>>,
         rs1: impl Into<Operand<'c>>,
-    ) -> std::io::Result<()> {
+    ) -> Result<()> {
         self.emit_triop("imul", rd, rs0, rs1)
     }
 
-    fn inc<'a>(&mut self, rd: impl Into<Operand<'a>>) -> std::io::Result<()> {
+    fn r#in<'a>(&mut self, rd: Reg, port: impl Into<Operand<'a>>) -> Result<()> {
+        let port = port.into();
+        self.check_port("in", rd, &port)?;
+        self.emit_binop("in", rd, port)
+    }
+
+    fn inc<'a>(&mut self, rd: impl Into<Operand<'a>>) -> Result<()> {
         self.emit_unop("inc", rd)
     }
 
-    fn jcc<'a>(&mut self, cc: Condition, rs: impl Into<Operand<'a>>) -> std::io::Result<()> {
+    fn inc2<'a, 'b>(
+        &mut self,
+        rd: impl Into<Operand<'a>>,
+        rs: impl Into<Operand<'b>>,
+    ) -> Result<()> {
+        self.check_feature("inc", Feature::Apx)?;
+        self.emit_binop("inc", rd, rs)
+    }
+
+    fn incsspq(&mut self, rs: Reg) -> Result<()> {
+        check_qword("incsspq", rs)?;
+        self.emit_unop("incsspq", rs)
+    }
+
+    fn int(&mut self, vector: u8) -> Result<()> {
+        self.emit_unop("int", vector)
+    }
+
+    fn int3(&mut self) -> Result<()> {
+        self.emit_nulop("int3")
+    }
+
+    fn into_(&mut self) -> Result<()> {
+        self.emit_nulop("into")
+    }
+
+    fn invlpg<'a>(&mut self, rs: Mem<'a>) -> Result<()> {
+        self.emit_unop("invlpg", rs)
+    }
+
+    fn iretq(&mut self) -> Result<()> {
+        self.emit_nulop("iretq")
+    }
+
+    fn jcc<'a>(&mut self, cc: Condition, rs: impl Into<Operand<'a>>) -> Result<()> {
+        let legal = match cc {
+            Condition::CXZ => self.bits != Bits::Bits64,
+            Condition::RCXZ => self.bits == Bits::Bits64,
+            _ => true,
+        };
+        if !legal {
+            let name = format!("j{cc:?}").to_lowercase();
+            return Err(Error::invalid(&name, &[], InvalidKind::NotInMode(self.bits)));
+        }
+        let rs = rs.into();
+        if cc.is_count_test()
+            && let Operand::Branch(Branch {
+                distance: Distance::Near,
+                ..
+            }) = rs
+        {
+            let name = format!("j{cc:?}").to_lowercase();
+            return Err(Error::invalid(&name, &[&rs], InvalidKind::IllegalOperand));
+        }
         self.emit_unop_cc("j", cc, rs)
     }
 
-    fn jmp<'a>(&mut self, rs: impl Into<Operand<'a>>) -> std::io::Result<()> {
-        self.emit_unop("jmp", rs)
+    fn jmp<'a>(&mut self, rs: impl Into<Operand<'a>>) -> Result<()> {
+        self.emit_indirect("jmp", rs)
     }
 
-    fn lea<'a>(&mut self, rd: Reg, rs: Mem<'a>) -> std::io::Result<()> {
+    fn jmp_far<'a>(&mut self, rs: impl Into<Operand<'a>>) -> Result<()> {
+        self.emit_far("jmp", rs)
+    }
+
+    fn lea<'a>(&mut self, rd: Reg, rs: Mem<'a>) -> Result<()> {
         self.emit_binop("lea", rd, rs)
     }
 
+    fn leave(&mut self) -> Result<()> {
+        self.emit_nulop("leave")
+    }
+
+    fn lfence(&mut self) -> Result<()> {
+        self.emit_nulop("lfence")
+    }
+
+    fn lgdt<'a>(&mut self, rs: Mem<'a>) -> Result<()> {
+        self.emit_unop("lgdt", rs)
+    }
+
+    fn lidt<'a>(&mut self, rs: Mem<'a>) -> Result<()> {
+        self.emit_unop("lidt", rs)
+    }
+
+    fn lldt<'a>(&mut self, rs: impl Into<Operand<'a>>) -> Result<()> {
+        self.emit_unop("lldt", rs)
+    }
+
+    fn r#loop<'a>(&mut self, rs: impl Into<Operand<'a>>) -> Result<()> {
+        self.emit_unop("loop", rs)
+    }
+
+    fn loope<'a>(&mut self, rs: impl Into<Operand<'a>>) -> Result<()> {
+        self.emit_unop("loope", rs)
+    }
+
+    fn loopne<'a>(&mut self, rs: impl Into<Operand<'a>>) -> Result<()> {
+        self.emit_unop("loopne", rs)
+    }
+
+    fn ltr<'a>(&mut self, rs: impl Into<Operand<'a>>) -> Result<()> {
+        self.emit_unop("ltr", rs)
+    }
+
     fn mov<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> std::io::Result<()> {
+    ) -> Result<()> {
+        let rd = rd.into();
+        let rs = rs.into();
+        self.check_mov_sreg(&rd, &rs)?;
+        self.check_mov_sys(&rd, &rs)?;
+
+        let narrow = self.features.contains(&Feature::NarrowMovImm);
+        let rd = match (rd, rs) {
+            (Operand::Reg(reg), Operand::Integer(0..=0xFFFF_FFFF))
+                if narrow && reg.size == RSize::QWord =>
+            {
+                Operand::Reg(reg.with_size(RSize::DWord))
+            }
+            _ => rd,
+        };
         self.emit_binop("mov", rd, rs)
     }
 
+    fn movntdq<'a>(&mut self, rd: Mem<'a>, rs: VReg) -> Result<()> {
+        self.check_sse("movntdq", rs, &Operand::Mem(rd))?;
+        self.emit_binop("movntdq", rd, rs)
+    }
+
+    fn movnti<'a>(&mut self, rd: Mem<'a>, rs: Reg) -> Result<()> {
+        if !matches!(rs.size, RSize::DWord | RSize::QWord) {
+            let ops = [&Operand::Mem(rd), &Operand::Reg(rs)];
+            return Err(Error::invalid("movnti", &ops, InvalidKind::NeedsWideRegister));
+        }
+        self.emit_binop("movnti", rd, rs)
+    }
+
+    fn movntps<'a>(&mut self, rd: Mem<'a>, rs: VReg) -> Result<()> {
+        self.check_sse("movntps", rs, &Operand::Mem(rd))?;
+        self.emit_binop("movntps", rd, rs)
+    }
+
     fn movsx<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> std::io::Result<()> {
+    ) -> Result<()> {
         self.emit_binop("movsx", rd, rs)
     }
 
+    fn movsxd<'a, 'b>(
+        &mut self,
+        rd: impl Into<Operand<'a>>,
+        rs: impl Into<Operand<'b>>,
+    ) -> Result<()> {
+        self.emit_binop("movsxd", rd, rs)
+    }
+
     fn movzx<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> std::io::Result<()> {
+    ) -> Result<()> {
         self.emit_binop("movzx", rd, rs)
     }
 
-    fn mul<'a>(&mut self, rs: impl Into<Operand<'a>>) -> std::io::Result<()> {
+    fn mfence(&mut self) -> Result<()> {
+        self.emit_nulop("mfence")
+    }
+
+    fn mul<'a>(&mut self, rs: impl Into<Operand<'a>>) -> Result<()> {
         self.emit_unop("mul", rs)
     }
 
-    fn neg<'a>(&mut self, rd: impl Into<Operand<'a>>) -> std::io::Result<()> {
+    fn neg<'a>(&mut self, rd: impl Into<Operand<'a>>) -> Result<()> {
         self.emit_unop("neg", rd)
     }
 
-    fn not<'a>(&mut self, rd: impl Into<Operand<'a>>) -> std::io::Result<()> {
+    fn not<'a>(&mut self, rd: impl Into<Operand<'a>>) -> Result<()> {
         self.emit_unop("not", rd)
     }
 
-    fn nop(&mut self) -> std::io::Result<()> {
+    fn neg2<'a, 'b>(
+        &mut self,
+        rd: impl Into<Operand<'a>>,
+        rs: impl Into<Operand<'b>>,
+    ) -> Result<()> {
+        self.check_feature("neg", Feature::Apx)?;
+        self.emit_binop("neg", rd, rs)
+    }
+
+    fn nop(&mut self) -> Result<()> {
         self.emit_nulop("nop")
     }
 
-    fn nop1<'a>(&mut self, rs: impl Into<Operand<'a>>) -> std::io::Result<()> {
+    fn nop1<'a>(&mut self, rs: impl Into<Operand<'a>>) -> Result<()> {
         self.emit_unop("nop", rs)
     }
 
+    fn not2<'a, 'b>(
+        &mut self,
+        rd: impl Into<Operand<'a>>,
+        rs: impl Into<Operand<'b>>,
+    ) -> Result<()> {
+        self.check_feature("not", Feature::Apx)?;
+        self.emit_binop("not", rd, rs)
+    }
+
     fn or<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> std::io::Result<()> {
+    ) -> Result<()> {
         self.emit_binop("or", rd, rs)
     }
 
-    fn pop<'a>(&mut self, rd: impl Into<Operand<'a>>) -> std::io::Result<()> {
+    fn or3<'a, 'b, 'c>(
+        &mut self,
+        rd: impl Into<Operand<'a>>,
+        rs0: impl Into<Operand<'b>>,
+        rs1: impl Into<Operand<'c>>,
+    ) -> Result<()> {
+        self.check_feature("or", Feature::Apx)?;
+        self.emit_triop("or", rd, rs0, rs1)
+    }
+
+    fn out<'a>(&mut self, port: impl Into<Operand<'a>>, rs: Reg) -> Result<()> {
+        let port = port.into();
+        self.check_port("out", rs, &port)?;
+        self.emit_binop("out", port, rs)
+    }
+
+    fn pause(&mut self) -> Result<()> {
+        self.emit_nulop("pause")
+    }
+
+    fn pclmulqdq<'a>(&mut self, rd: VReg, rs: impl Into<Operand<'a>>, imm: u8) -> Result<()> {
+        self.emit_sse_imm("pclmulqdq", rd, rs, imm)
+    }
+
+    fn pop<'a>(&mut self, rd: impl Into<Operand<'a>>) -> Result<()> {
         self.emit_unop("pop", rd)
     }
 
-    fn push<'a>(&mut self, rs: impl Into<Operand<'a>>) -> std::io::Result<()> {
+    fn pop2(&mut self, rd0: Reg, rd1: Reg) -> Result<()> {
+        self.check_pair("pop2", rd0, rd1)?;
+        self.emit_binop("pop2", rd0, rd1)
+    }
+
+    fn prefetchnta<'a>(&mut self, rs: Mem<'a>) -> Result<()> {
+        self.emit_unop("prefetchnta", rs)
+    }
+
+    fn prefetcht0<'a>(&mut self, rs: Mem<'a>) -> Result<()> {
+        self.emit_unop("prefetcht0", rs)
+    }
+
+    fn prefetcht1<'a>(&mut self, rs: Mem<'a>) -> Result<()> {
+        self.emit_unop("prefetcht1", rs)
+    }
+
+    fn prefetcht2<'a>(&mut self, rs: Mem<'a>) -> Result<()> {
+        self.emit_unop("prefetcht2", rs)
+    }
+
+    fn prefetchw<'a>(&mut self, rs: Mem<'a>) -> Result<()> {
+        self.emit_unop("prefetchw", rs)
+    }
+
+    fn push<'a>(&mut self, rs: impl Into<Operand<'a>>) -> Result<()> {
         self.emit_unop("push", rs)
     }
 
+    fn push2(&mut self, rs0: Reg, rs1: Reg) -> Result<()> {
+        self.check_pair("push2", rs0, rs1)?;
+        self.emit_binop("push2", rs0, rs1)
+    }
+
     fn rcl<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> std::io::Result<()> {
+    ) -> Result<()> {
         self.emit_binop("rcl", rd, rs)
     }
 
@@ -549,19 +1870,68 @@ impl<O: Write> X86Writer for NasmWriter<O> {
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> std::io::Result<()> {
+    ) -> Result<()> {
         self.emit_binop("rcr", rd, rs)
     }
 
-    fn ret(&mut self) -> std::io::Result<()> {
+    fn rdmsr(&mut self) -> Result<()> {
+        self.emit_nulop("rdmsr")
+    }
+
+    fn rdpid(&mut self, rd: Reg) -> Result<()> {
+        if self.bits == Bits::Bits64 {
+            check_qword("rdpid", rd)?;
+        } else if !matches!(rd.size, RSize::DWord | RSize::QWord) {
+            let ops = [&Operand::Reg(rd)];
+            return Err(Error::invalid("rdpid", &ops, InvalidKind::NeedsWideRegister));
+        }
+        self.emit_unop("rdpid", rd)
+    }
+
+    fn rdrand(&mut self, rd: Reg) -> Result<()> {
+        self.check_not_byte("rdrand", rd)?;
+        self.emit_unop("rdrand", rd)
+    }
+
+    fn rdseed(&mut self, rd: Reg) -> Result<()> {
+        self.check_not_byte("rdseed", rd)?;
+        self.emit_unop("rdseed", rd)
+    }
+
+    fn rdsspq(&mut self, rd: Reg) -> Result<()> {
+        check_qword("rdsspq", rd)?;
+        self.emit_unop("rdsspq", rd)
+    }
+
+    fn rdtsc(&mut self) -> Result<()> {
+        self.emit_nulop("rdtsc")
+    }
+
+    fn rdtscp(&mut self) -> Result<()> {
+        self.emit_nulop("rdtscp")
+    }
+
+    fn ret(&mut self) -> Result<()> {
         self.emit_nulop("ret")
     }
 
+    fn ret1(&mut self, pop: u16) -> Result<()> {
+        self.emit_unop("ret", pop)
+    }
+
+    fn retf(&mut self) -> Result<()> {
+        self.emit_nulop("retf")
+    }
+
+    fn retf1(&mut self, pop: u16) -> Result<()> {
+        self.emit_unop("retf", pop)
+    }
+
     fn rol<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> std::io::Result<()> {
+    ) -> Result<()> {
         self.emit_binop("rol", rd, rs)
     }
 
@@ -569,15 +1939,19 @@ impl<O: Write> X86Writer for NasmWriter<O> {
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> std::io::Result<()> {
+    ) -> Result<()> {
         self.emit_binop("ror", rd, rs)
     }
 
+    fn rstorssp<'a>(&mut self, rs: Mem<'a>) -> Result<()> {
+        self.emit_unop("rstorssp", rs)
+    }
+
     fn sal<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> std::io::Result<()> {
+    ) -> Result<()> {
         self.emit_binop("sal", rd, rs)
     }
 
@@ -585,27 +1959,84 @@ impl<O: Write> X86Writer for NasmWriter<O> {
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> std::io::Result<()> {
+    ) -> Result<()> {
         self.emit_binop("sar", rd, rs)
     }
 
+    fn saveprevssp(&mut self) -> Result<()> {
+        self.emit_nulop("saveprevssp")
+    }
+
     fn sbb<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> std::io::Result<()> {
+    ) -> Result<()> {
         self.emit_binop("sbb", rd, rs)
     }
 
-    fn setcc<'a>(&mut self, cc: Condition, dst: impl Into<Operand<'a>>) -> io::Result<()> {
+    fn sbb3<'a, 'b, 'c>(
+        &mut self,
+        rd: impl Into<Operand<'a>>,
+        rs0: impl Into<Operand<'b>>,
+        rs1: impl Into<Operand<'c>>,
+    ) -> Result<()> {
+        self.check_feature("sbb", Feature::Apx)?;
+        self.emit_triop("sbb", rd, rs0, rs1)
+    }
+
+    fn serialize(&mut self) -> Result<()> {
+        self.emit_nulop("serialize")
+    }
+
+    fn setcc<'a>(&mut self, cc: Condition, dst: impl Into<Operand<'a>>) -> Result<()> {
+        self.check_cc("set", cc)?;
         self.emit_unop_cc("set", cc, dst)
     }
 
+    fn sfence(&mut self) -> Result<()> {
+        self.emit_nulop("sfence")
+    }
+
+    fn sgdt<'a>(&mut self, rd: Mem<'a>) -> Result<()> {
+        self.emit_unop("sgdt", rd)
+    }
+
+    fn sha1msg1<'a>(&mut self, rd: VReg, rs: impl Into<Operand<'a>>) -> Result<()> {
+        self.emit_sse("sha1msg1", rd, rs)
+    }
+
+    fn sha1msg2<'a>(&mut self, rd: VReg, rs: impl Into<Operand<'a>>) -> Result<()> {
+        self.emit_sse("sha1msg2", rd, rs)
+    }
+
+    fn sha1nexte<'a>(&mut self, rd: VReg, rs: impl Into<Operand<'a>>) -> Result<()> {
+        self.emit_sse("sha1nexte", rd, rs)
+    }
+
+    fn sha1rnds4<'a>(&mut self, rd: VReg, rs: impl Into<Operand<'a>>, imm: u8) -> Result<()> {
+        self.emit_sse_imm("sha1rnds4", rd, rs, imm)
+    }
+
+    fn sha256msg1<'a>(&mut self, rd: VReg, rs: impl Into<Operand<'a>>) -> Result<()> {
+        self.emit_sse("sha256msg1", rd, rs)
+    }
+
+    fn sha256msg2<'a>(&mut self, rd: VReg, rs: impl Into<Operand<'a>>) -> Result<()> {
+        self.emit_sse("sha256msg2", rd, rs)
+    }
+
+    fn sha256rnds2<'a>(&mut self, rd: VReg, rs: impl Into<Operand<'a>>) -> Result<()> {
+        let rs = rs.into();
+        self.check_sse("sha256rnds2", rd, &rs)?;
+        self.emit_triop("sha256rnds2", rd, rs, XMM0)
+    }
+
     fn shl<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> std::io::Result<()> {
+    ) -> Result<()> {
         self.emit_binop("shl", rd, rs)
     }
 
@@ -613,19 +2044,23 @@ impl<O: Write> X86Writer for NasmWriter<O> {
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> std::io::Result<()> {
+    ) -> Result<()> {
         self.emit_binop("shr", rd, rs)
     }
 
-    fn stc(&mut self) -> std::io::Result<()> {
+    fn sidt<'a>(&mut self, rd: Mem<'a>) -> Result<()> {
+        self.emit_unop("sidt", rd)
+    }
+
+    fn stc(&mut self) -> Result<()> {
         self.emit_nulop("stc")
     }
 
-    fn std(&mut self) -> std::io::Result<()> {
+    fn std(&mut self) -> Result<()> {
         self.emit_nulop("std")
     }
 
-    fn sti(&mut self) -> std::io::Result<()> {
+    fn sti(&mut self) -> Result<()> {
         self.emit_nulop("sti")
     }
 
@@ -633,35 +2068,383 @@ impl<O: Write> X86Writer for NasmWriter<O> {
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> std::io::Result<()> {
+    ) -> Result<()> {
         self.emit_binop("sub", rd, rs)
     }
 
-    fn syscall(&mut self) -> std::io::Result<()> {
+    fn sub3<'a, 'b, 'c>(
+        &mut self,
+        rd: impl Into<Operand<'a>>,
+        rs0: impl Into<Operand<'b>>,
+        rs1: impl Into<Operand<'c>>,
+    ) -> Result<()> {
+        self.check_feature("sub", Feature::Apx)?;
+        self.emit_triop("sub", rd, rs0, rs1)
+    }
+
+    fn swapgs(&mut self) -> Result<()> {
+        self.emit_nulop("swapgs")
+    }
+
+    fn syscall(&mut self) -> Result<()> {
         self.emit_nulop("syscall")
     }
 
+    fn sysexit(&mut self) -> Result<()> {
+        self.emit_nulop("sysexit")
+    }
+
+    fn sysret(&mut self) -> Result<()> {
+        self.emit_nulop("sysret")
+    }
+
+    fn sysretq(&mut self) -> Result<()> {
+        self.emit_nulop("o64 sysret")
+    }
+
     fn test<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> std::io::Result<()> {
+    ) -> Result<()> {
         self.emit_binop("test", rd, rs)
     }
 
+    fn ud2(&mut self) -> Result<()> {
+        self.emit_nulop("ud2")
+    }
+
+    fn vcvtph2ps<'a>(&mut self, rd: VReg, rs: impl Into<Operand<'a>>) -> Result<()> {
+        let rs = rs.into();
+        let valid = match rs {
+            Operand::VReg(r) => r.size == VSize::Xmm,
+            Operand::Mem(_) => true,
+            _ => false,
+        };
+        if !valid {
+            let ops = [&Operand::VReg(rd), &rs];
+            return Err(Error::invalid("vcvtph2ps", &ops, InvalidKind::IllegalOperand));
+        }
+        self.emit_binop("vcvtph2ps", rd, rs)
+    }
+
+    fn vcvtps2ph<'a>(&mut self, rd: impl Into<Operand<'a>>, rs: VReg, imm: u8) -> Result<()> {
+        let rd = rd.into();
+        let valid = match rd {
+            Operand::VReg(r) => r.size == VSize::Xmm,
+            Operand::Mem(_) => true,
+            _ => false,
+        };
+        if !valid {
+            let ops = [&rd, &Operand::VReg(rs)];
+            return Err(Error::invalid("vcvtps2ph", &ops, InvalidKind::IllegalOperand));
+        }
+        self.emit_triop("vcvtps2ph", rd, rs, imm)
+    }
+
+    fn vfmadd132<'a>(
+        &mut self,
+        ty: FpType,
+        dst_a: VReg,
+        c: VReg,
+        b: impl Into<Operand<'a>>,
+    ) -> Result<()> {
+        self.emit_fma("vfmadd132", ty, dst_a, c, b)
+    }
+
+    fn vfmadd213<'a>(
+        &mut self,
+        ty: FpType,
+        dst_b: VReg,
+        a: VReg,
+        c: impl Into<Operand<'a>>,
+    ) -> Result<()> {
+        self.emit_fma("vfmadd213", ty, dst_b, a, c)
+    }
+
+    fn vfmadd231<'a>(
+        &mut self,
+        ty: FpType,
+        dst_c: VReg,
+        a: VReg,
+        b: impl Into<Operand<'a>>,
+    ) -> Result<()> {
+        self.emit_fma("vfmadd231", ty, dst_c, a, b)
+    }
+
+    fn vfmaddsub132<'a>(
+        &mut self,
+        ty: FpType,
+        dst_a: VReg,
+        c: VReg,
+        b: impl Into<Operand<'a>>,
+    ) -> Result<()> {
+        self.emit_fma("vfmaddsub132", ty, dst_a, c, b)
+    }
+
+    fn vfmaddsub213<'a>(
+        &mut self,
+        ty: FpType,
+        dst_b: VReg,
+        a: VReg,
+        c: impl Into<Operand<'a>>,
+    ) -> Result<()> {
+        self.emit_fma("vfmaddsub213", ty, dst_b, a, c)
+    }
+
+    fn vfmaddsub231<'a>(
+        &mut self,
+        ty: FpType,
+        dst_c: VReg,
+        a: VReg,
+        b: impl Into<Operand<'a>>,
+    ) -> Result<()> {
+        self.emit_fma("vfmaddsub231", ty, dst_c, a, b)
+    }
+
+    fn vfmsub132<'a>(
+        &mut self,
+        ty: FpType,
+        dst_a: VReg,
+        c: VReg,
+        b: impl Into<Operand<'a>>,
+    ) -> Result<()> {
+        self.emit_fma("vfmsub132", ty, dst_a, c, b)
+    }
+
+    fn vfmsub213<'a>(
+        &mut self,
+        ty: FpType,
+        dst_b: VReg,
+        a: VReg,
+        c: impl Into<Operand<'a>>,
+    ) -> Result<()> {
+        self.emit_fma("vfmsub213", ty, dst_b, a, c)
+    }
+
+    fn vfmsub231<'a>(
+        &mut self,
+        ty: FpType,
+        dst_c: VReg,
+        a: VReg,
+        b: impl Into<Operand<'a>>,
+    ) -> Result<()> {
+        self.emit_fma("vfmsub231", ty, dst_c, a, b)
+    }
+
+    fn vfmsubadd132<'a>(
+        &mut self,
+        ty: FpType,
+        dst_a: VReg,
+        c: VReg,
+        b: impl Into<Operand<'a>>,
+    ) -> Result<()> {
+        self.emit_fma("vfmsubadd132", ty, dst_a, c, b)
+    }
+
+    fn vfmsubadd213<'a>(
+        &mut self,
+        ty: FpType,
+        dst_b: VReg,
+        a: VReg,
+        c: impl Into<Operand<'a>>,
+    ) -> Result<()> {
+        self.emit_fma("vfmsubadd213", ty, dst_b, a, c)
+    }
+
+    fn vfmsubadd231<'a>(
+        &mut self,
+        ty: FpType,
+        dst_c: VReg,
+        a: VReg,
+        b: impl Into<Operand<'a>>,
+    ) -> Result<()> {
+        self.emit_fma("vfmsubadd231", ty, dst_c, a, b)
+    }
+
+    fn vfnmadd132<'a>(
+        &mut self,
+        ty: FpType,
+        dst_a: VReg,
+        c: VReg,
+        b: impl Into<Operand<'a>>,
+    ) -> Result<()> {
+        self.emit_fma("vfnmadd132", ty, dst_a, c, b)
+    }
+
+    fn vfnmadd213<'a>(
+        &mut self,
+        ty: FpType,
+        dst_b: VReg,
+        a: VReg,
+        c: impl Into<Operand<'a>>,
+    ) -> Result<()> {
+        self.emit_fma("vfnmadd213", ty, dst_b, a, c)
+    }
+
+    fn vfnmadd231<'a>(
+        &mut self,
+        ty: FpType,
+        dst_c: VReg,
+        a: VReg,
+        b: impl Into<Operand<'a>>,
+    ) -> Result<()> {
+        self.emit_fma("vfnmadd231", ty, dst_c, a, b)
+    }
+
+    fn vfnmsub132<'a>(
+        &mut self,
+        ty: FpType,
+        dst_a: VReg,
+        c: VReg,
+        b: impl Into<Operand<'a>>,
+    ) -> Result<()> {
+        self.emit_fma("vfnmsub132", ty, dst_a, c, b)
+    }
+
+    fn vfnmsub213<'a>(
+        &mut self,
+        ty: FpType,
+        dst_b: VReg,
+        a: VReg,
+        c: impl Into<Operand<'a>>,
+    ) -> Result<()> {
+        self.emit_fma("vfnmsub213", ty, dst_b, a, c)
+    }
+
+    fn vfnmsub231<'a>(
+        &mut self,
+        ty: FpType,
+        dst_c: VReg,
+        a: VReg,
+        b: impl Into<Operand<'a>>,
+    ) -> Result<()> {
+        self.emit_fma("vfnmsub231", ty, dst_c, a, b)
+    }
+
+    fn wbinvd(&mut self) -> Result<()> {
+        self.emit_nulop("wbinvd")
+    }
+
+    fn wrmsr(&mut self) -> Result<()> {
+        self.emit_nulop("wrmsr")
+    }
+
+    fn xgetbv(&mut self) -> Result<()> {
+        self.emit_nulop("xgetbv")
+    }
+
     fn xor<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> std::io::Result<()> {
+    ) -> Result<()> {
         self.emit_binop("xor", rd, rs)
     }
+
+    fn xor3<'a, 'b, 'c>(
+        &mut self,
+        rd: impl Into<Operand<'a>>,
+        rs0: impl Into<Operand<'b>>,
+        rs1: impl Into<Operand<'c>>,
+    ) -> Result<()> {
+        self.check_feature("xor", Feature::Apx)?;
+        self.emit_triop("xor", rd, rs0, rs1)
+    }
+
+    fn xrstor<'a>(&mut self, rs: Mem<'a>) -> Result<()> {
+        self.emit_unop("xrstor", rs)
+    }
+
+    fn xrstor64<'a>(&mut self, rs: Mem<'a>) -> Result<()> {
+        self.emit_unop("xrstor64", rs)
+    }
+
+    fn xsave<'a>(&mut self, rd: Mem<'a>) -> Result<()> {
+        self.emit_unop("xsave", rd)
+    }
+
+    fn xsave64<'a>(&mut self, rd: Mem<'a>) -> Result<()> {
+        self.emit_unop("xsave64", rd)
+    }
+}
+
+pub(crate) fn check_qword(name: &str, reg: Reg) -> Result<()> {
+    if reg.size != RSize::QWord {
+        let ops = [&Operand::Reg(reg)];
+        return Err(Error::invalid(name, &ops, InvalidKind::NeedsQWord));
+    }
+
+    Ok(())
+}
+pub(crate) fn render_operand(op: &Operand) -> String {
+    let mut w = NasmWriter::new(Vec::new());
+    match w.print_operand("", op) {
+        Ok(()) => String::from_utf8(w.out).unwrap(),
+        Err(_) => format!("{op:?}"),
+    }
 }
 
+fn is_local(label: &str) -> bool {
+    label.starts_with('.') && !label.starts_with("..")
+}
+fn is_long_only(name: &str) -> bool {
+    matches!(name, "cqo" | "iretq" | "o64 sysret" | "swapgs" | "xrstor64" | "xsave64")
+}
+fn is_same_size(name: &str) -> bool {
+    matches!(
+        name,
+        "adc"
+            | "add"
+            | "and"
+            | "cmp"
+            | "cmov"
+            | "imul"
+            | "mov"
+            | "or"
+            | "sbb"
+            | "sub"
+            | "test"
+            | "xor"
+    )
+}
+fn is_shift(name: &str) -> bool {
+    matches!(
+        name,
+        "rcl" | "rcr" | "rol" | "ror" | "sal" | "sar" | "shl" | "shr"
+    )
+}
+fn is_rm_only(name: &str) -> bool {
+    matches!(
+        name,
+        "dec" | "div" | "idiv" | "inc" | "mul" | "neg" | "not" | "pop" | "set"
+    )
+}
+
+fn fold_imm<'a>(dst: &Operand, op: Operand<'a>) -> Operand<'a> {
+    match op {
+        Operand::Integer(value) if value > i64::MAX as i128 && dst.size() == Some(OSize::QWord) => {
+            Operand::Integer(value as u64 as i64 as i128)
+        }
+        op => op,
+    }
+}
+
+fn imm_fits(value: i128, size: OSize, imm64: bool) -> bool {
+    let as_qword = (i64::MIN as i128..=u64::MAX as i128).contains(&value);
+    match size {
+        OSize::Byte => (-0x80..=0xFF).contains(&value),
+        OSize::Word => (-0x8000..=0xFFFF).contains(&value),
+        OSize::DWord => (-0x8000_0000..=0xFFFF_FFFF).contains(&value),
+        OSize::QWord if imm64 => as_qword,
+        OSize::QWord => as_qword && i32::try_from(value as u64 as i64).is_ok(),
+        _ => true,
+    }
+}
 
 fn is_ascii_printable(bytes: &[u8]) -> bool {
     for &byte in bytes {
-        if byte > 127 || byte < 32 || byte == b'"' { return false };
+        if !(32..=127).contains(&byte) || byte == b'"' { return false };
     }
 
     true
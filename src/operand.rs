@@ -1,22 +1,60 @@
 use crate::{
+    expr::Expr,
     gp_regs::{RSize, Reg},
+    label::Label,
     mem::Mem,
+    sys_regs::{CReg, DReg, SReg},
+    vec_regs::{VReg, VSize},
 };
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Operand<'a> {
     Reg(Reg),
+    SReg(SReg),
+    CReg(CReg),
+    DReg(DReg),
+    VReg(VReg),
     Mem(Mem<'a>),
-    Integer(i64),
+    /// Wide enough for every `i64` and `u64` immediate.
+    Integer(i128),
     Label(&'a str),
+    Expr(&'a Expr<'a>),
+    Far(FarPtr<'a>),
+    Branch(Branch<'a>),
 }
 impl Operand<'_> {
     pub fn size(&self) -> Option<OSize> {
         match self {
             Self::Reg(r) => Some(r.size.into()),
+            Self::SReg(_) => Some(OSize::Word),
+            Self::CReg(_) => Some(OSize::QWord),
+            Self::DReg(_) => Some(OSize::QWord),
+            Self::VReg(r) => Some(r.size.into()),
             Self::Mem(mem) => mem.size,
             Self::Integer(_) => None,
             Self::Label(_) => None,
+            Self::Expr(_) => None,
+            Self::Far(_) => None,
+            Self::Branch(_) => None,
+        }
+    }
+
+    pub fn is_high_byte(&self) -> bool {
+        match self {
+            Self::Reg(r) => r.is_high_byte(),
+            _ => false,
+        }
+    }
+    pub fn needs_rex(&self) -> bool {
+        match self {
+            Self::Reg(r) => r.needs_rex(),
+            Self::VReg(r) => r.name as u8 >= 8,
+            Self::Mem(mem) => {
+                let base = mem.base.is_some_and(|r| r.name.is_extended());
+                let index = mem.index.is_some_and(|(r, _)| r.name.is_extended());
+                base || index
+            }
+            _ => false,
         }
     }
 }
@@ -25,6 +63,26 @@ impl From<Reg> for Operand<'static> {
         Self::Reg(value)
     }
 }
+impl From<SReg> for Operand<'static> {
+    fn from(value: SReg) -> Self {
+        Self::SReg(value)
+    }
+}
+impl From<CReg> for Operand<'static> {
+    fn from(value: CReg) -> Self {
+        Self::CReg(value)
+    }
+}
+impl From<DReg> for Operand<'static> {
+    fn from(value: DReg) -> Self {
+        Self::DReg(value)
+    }
+}
+impl From<VReg> for Operand<'static> {
+    fn from(value: VReg) -> Self {
+        Self::VReg(value)
+    }
+}
 impl<'a> From<Mem<'a>> for Operand<'a> {
     fn from(value: Mem<'a>) -> Self {
         Self::Mem(value)
@@ -32,42 +90,42 @@ impl<'a> From<Mem<'a>> for Operand<'a> {
 }
 impl From<i64> for Operand<'static> {
     fn from(value: i64) -> Self {
-        Self::Integer(value)
+        Self::Integer(value as i128)
     }
 }
 impl From<i32> for Operand<'static> {
     fn from(value: i32) -> Self {
-        Self::Integer(value as i64)
+        Self::Integer(value as i128)
     }
 }
 impl From<i16> for Operand<'static> {
     fn from(value: i16) -> Self {
-        Self::Integer(value as i64)
+        Self::Integer(value as i128)
     }
 }
 impl From<i8> for Operand<'static> {
     fn from(value: i8) -> Self {
-        Self::Integer(value as i64)
+        Self::Integer(value as i128)
     }
 }
 impl From<u64> for Operand<'static> {
     fn from(value: u64) -> Self {
-        Self::Integer(value as i64)
+        Self::Integer(value as i128)
     }
 }
 impl From<u32> for Operand<'static> {
     fn from(value: u32) -> Self {
-        Self::Integer(value as i64)
+        Self::Integer(value as i128)
     }
 }
 impl From<u16> for Operand<'static> {
     fn from(value: u16) -> Self {
-        Self::Integer(value as i64)
+        Self::Integer(value as i128)
     }
 }
 impl From<u8> for Operand<'static> {
     fn from(value: u8) -> Self {
-        Self::Integer(value as i64)
+        Self::Integer(value as i128)
     }
 }
 impl<'a> From<&'a str> for Operand<'a> {
@@ -80,6 +138,81 @@ impl<'a> From<&'a String> for Operand<'a> {
         Self::Label(value)
     }
 }
+impl<'a> From<&'a Label> for Operand<'a> {
+    fn from(value: &'a Label) -> Self {
+        Self::Label(value.name())
+    }
+}
+impl<'a> From<&'a Expr<'a>> for Operand<'a> {
+    fn from(value: &'a Expr<'a>) -> Self {
+        Self::Expr(value)
+    }
+}
+impl<'a> From<FarPtr<'a>> for Operand<'a> {
+    fn from(value: FarPtr<'a>) -> Self {
+        Self::Far(value)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FarPtr<'a> {
+    pub segment: u16,
+    pub offset: i64,
+    pub label: Option<&'a str>,
+}
+impl FarPtr<'static> {
+    pub fn new(segment: u16, offset: i64) -> Self {
+        Self {
+            segment,
+            offset,
+            label: None,
+        }
+    }
+}
+impl<'a> FarPtr<'a> {
+    pub fn labeled(segment: u16, label: &'a str) -> Self {
+        Self {
+            segment,
+            offset: 0,
+            label: Some(label),
+        }
+    }
+}
+
+impl<'a> From<Branch<'a>> for Operand<'a> {
+    fn from(value: Branch<'a>) -> Self {
+        Self::Branch(value)
+    }
+}
+
+/// Forces the encoding of a `jmp`/`jcc`. Unmarked label targets are relaxed by NASM's
+/// optimiser, which also rejects a forced `short` that is out of range; `NasmWriter` has no
+/// addresses and does neither itself.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Branch<'a> {
+    pub target: &'a str,
+    pub distance: Distance,
+}
+impl<'a> Branch<'a> {
+    pub fn short(target: &'a str) -> Self {
+        Self {
+            target,
+            distance: Distance::Short,
+        }
+    }
+    pub fn near(target: &'a str) -> Self {
+        Self {
+            target,
+            distance: Distance::Near,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Distance {
+    Short,
+    Near,
+}
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum OSize {
@@ -87,14 +220,25 @@ pub enum OSize {
     Word,
     DWord,
     QWord,
+    OWord,
+    YWord,
 }
 impl From<RSize> for OSize {
     fn from(value: RSize) -> Self {
         match value {
             RSize::Byte => Self::Byte,
+            RSize::HighByte => Self::Byte,
             RSize::Word => Self::Word,
             RSize::DWord => Self::DWord,
             RSize::QWord => Self::QWord,
         }
     }
 }
+impl From<VSize> for OSize {
+    fn from(value: VSize) -> Self {
+        match value {
+            VSize::Xmm => Self::OWord,
+            VSize::Ymm => Self::YWord,
+        }
+    }
+}
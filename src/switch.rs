@@ -0,0 +1,243 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    Error, Result,
+    data::Data,
+    error::InvalidKind,
+    expr::Expr,
+    gp_regs::{RSize, Reg},
+    mem::{Mem, Scale},
+    operand::OSize,
+    writer::{Condition, X86Writer},
+};
+
+/// `value` and `scratch` are both clobbered: `value` is rebased by `sub` and, for a jump
+/// table, overwritten by `movsxd`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Switch<'a> {
+    pub value: Reg,
+    pub scratch: Reg,
+    pub cases: BTreeMap<i64, &'a str>,
+    pub default: &'a str,
+    pub entries: TableEntries,
+}
+impl<'a> Switch<'a> {
+    pub fn new(value: Reg, scratch: Reg, default: &'a str) -> Self {
+        Self {
+            value,
+            scratch,
+            cases: BTreeMap::new(),
+            default,
+            entries: TableEntries::Relative,
+        }
+    }
+
+    pub fn case(mut self, key: i64, target: &'a str) -> Self {
+        self.cases.insert(key, target);
+        self
+    }
+    pub fn absolute(mut self) -> Self {
+        self.entries = TableEntries::Absolute;
+        self
+    }
+
+    pub fn strategy(&self) -> Strategy {
+        let (Some((&min, _)), Some((&max, _))) =
+            (self.cases.first_key_value(), self.cases.last_key_value())
+        else {
+            return Strategy::Search;
+        };
+        let count = self.cases.len() as i128;
+        let range = max as i128 - min as i128;
+        let targets: BTreeSet<&str> = self.cases.values().copied().collect();
+
+        if count >= 3 && range < 64 && targets.len() <= 3 {
+            Strategy::BitTest
+        } else if count >= 4 && range < 3 * count && range <= u32::MAX as i128 {
+            Strategy::Table
+        } else {
+            Strategy::Search
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TableEntries {
+    Relative,
+    Absolute,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Strategy {
+    Table,
+    Search,
+    BitTest,
+}
+
+/// Clobbers `switch.value` and `switch.scratch`. A jump table is placed in `.rodata`, after
+/// which the writer switches to `.text`, whatever section it was in before. Dword keys are
+/// compared as 32-bit values, so `0xFFFF_FFFF` and `-1` name the same case.
+pub fn emit_switch(w: &mut impl X86Writer, switch: &Switch) -> Result<()> {
+    if !matches!(switch.value.size, RSize::DWord | RSize::QWord) {
+        return Err(Error::invalid(
+            "switch",
+            &[&switch.value.into()],
+            InvalidKind::IllegalOperand,
+        ));
+    }
+
+    if switch.value.size == RSize::DWord {
+        let switch = sign_normalise(switch)?;
+        return emit_strategy(w, &switch);
+    }
+    emit_strategy(w, switch)
+}
+
+fn sign_normalise<'a>(switch: &Switch<'a>) -> Result<Switch<'a>> {
+    let mut cases = BTreeMap::new();
+    for (&key, &target) in &switch.cases {
+        if !(i32::MIN as i64..=u32::MAX as i64).contains(&key) {
+            let kind = InvalidKind::ImmediateTooWide;
+            return Err(Error::invalid("switch", &[&key.into()], kind));
+        }
+        if cases.insert(key as i32 as i64, target).is_some() {
+            let kind = InvalidKind::IllegalOperand;
+            return Err(Error::invalid("switch", &[&key.into()], kind));
+        }
+    }
+
+    Ok(Switch {
+        cases,
+        ..switch.clone()
+    })
+}
+
+fn emit_strategy(w: &mut impl X86Writer, switch: &Switch) -> Result<()> {
+    match switch.strategy() {
+        Strategy::Table => emit_table(w, switch),
+        Strategy::Search => {
+            let cases: Vec<(i64, &str)> = switch.cases.iter().map(|(&k, &t)| (k, t)).collect();
+            emit_search(w, switch, &cases)
+        }
+        Strategy::BitTest => emit_bit_test(w, switch),
+    }
+}
+
+fn emit_table(w: &mut impl X86Writer, switch: &Switch) -> Result<()> {
+    let (&min, _) = switch.cases.first_key_value().unwrap();
+    let (&max, _) = switch.cases.last_key_value().unwrap();
+    let range = max.wrapping_sub(min) as u64;
+    let index = switch.value.with_size(RSize::QWord);
+    let scratch = switch.scratch.with_size(RSize::QWord);
+    let table = w.new_label();
+
+    emit_bounds(w, switch, min, range)?;
+    w.lea(scratch, Mem::new().rip_relative() + &table)?;
+    match switch.entries {
+        TableEntries::Relative => {
+            let entry = scratch.mem() + (index, Scale::Four) + OSize::DWord;
+            w.movsxd(index, entry)?;
+            w.add(scratch, index)?;
+            w.jmp(scratch)?;
+        }
+        TableEntries::Absolute => {
+            let entry = scratch.mem() + (index, Scale::Eight) + OSize::QWord;
+            w.jmp(entry)?;
+        }
+    }
+
+    let entries: Vec<Data> = (0..=range)
+        .map(|i| {
+            let key = min.wrapping_add_unsigned(i);
+            let target = switch.cases.get(&key).copied().unwrap_or(switch.default);
+            match switch.entries {
+                TableEntries::Relative => (Expr::sym(target) - &table).into(),
+                TableEntries::Absolute => target.into(),
+            }
+        })
+        .collect();
+    w.rodata()?;
+    w.bind(&table)?;
+    match switch.entries {
+        TableEntries::Relative => w.dd("", &entries)?,
+        TableEntries::Absolute => w.dq("", &entries)?,
+    }
+    w.text()
+}
+
+fn emit_search(w: &mut impl X86Writer, switch: &Switch, cases: &[(i64, &str)]) -> Result<()> {
+    if cases.len() <= 3 {
+        for &(key, target) in cases {
+            emit_cmp(w, switch, key)?;
+            w.jcc(Condition::E, target)?;
+        }
+        return w.jmp(switch.default);
+    }
+
+    let mid = cases.len() / 2;
+    let (key, target) = cases[mid];
+    let left = w.new_label();
+    emit_cmp(w, switch, key)?;
+    w.jcc(Condition::E, target)?;
+    w.jcc(Condition::L, &left)?;
+    emit_search(w, switch, &cases[mid + 1..])?;
+    w.bind(&left)?;
+    emit_search(w, switch, &cases[..mid])
+}
+
+fn emit_bit_test(w: &mut impl X86Writer, switch: &Switch) -> Result<()> {
+    let (&min, _) = switch.cases.first_key_value().unwrap();
+    let (&max, _) = switch.cases.last_key_value().unwrap();
+    let range = max.wrapping_sub(min) as u64;
+    let index = switch.value.with_size(RSize::QWord);
+    let scratch = switch.scratch.with_size(RSize::QWord);
+
+    let mut masks: BTreeMap<&str, u64> = BTreeMap::new();
+    for (&key, &target) in &switch.cases {
+        *masks.entry(target).or_default() |= 1 << key.wrapping_sub(min);
+    }
+
+    emit_bounds(w, switch, min, range)?;
+    for (target, mask) in masks {
+        w.mov(scratch, mask)?;
+        w.bt(scratch, index)?;
+        w.jcc(Condition::C, target)?;
+    }
+    w.jmp(switch.default)
+}
+
+fn emit_bounds(w: &mut impl X86Writer, switch: &Switch, min: i64, range: u64) -> Result<()> {
+    let value = switch.value;
+    let scratch = switch.scratch.with_size(value.size);
+
+    if value.size == RSize::DWord {
+        w.mov(value, value)?;
+    }
+    if min != 0 {
+        if i32::try_from(min).is_ok() {
+            w.sub(value, min)?;
+        } else {
+            w.mov(scratch, min)?;
+            w.sub(value, scratch)?;
+        }
+    }
+    if i32::try_from(range).is_ok() {
+        w.cmp(value, range)?;
+    } else {
+        w.mov(scratch, range)?;
+        w.cmp(value, scratch)?;
+    }
+    w.jcc(Condition::A, switch.default)
+}
+
+fn emit_cmp(w: &mut impl X86Writer, switch: &Switch, key: i64) -> Result<()> {
+    let value = switch.value;
+
+    if i32::try_from(key).is_ok() {
+        w.cmp(value, key)
+    } else {
+        let scratch = switch.scratch.with_size(value.size);
+        w.mov(scratch, key)?;
+        w.cmp(value, scratch)
+    }
+}
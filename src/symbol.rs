@@ -0,0 +1,72 @@
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Symbol<'a> {
+    pub name: &'a str,
+    pub kind: Option<SymbolKind>,
+    pub visibility: Option<Visibility>,
+    pub weak: bool,
+    pub size: Option<SymbolSize>,
+}
+impl<'a> Symbol<'a> {
+    pub fn new(name: &'a str) -> Self {
+        Self {
+            name,
+            kind: None,
+            visibility: None,
+            weak: false,
+            size: None,
+        }
+    }
+
+    pub fn function(self) -> Self {
+        self.kinded(SymbolKind::Function)
+    }
+    pub fn data(self) -> Self {
+        self.kinded(SymbolKind::Data)
+    }
+    pub fn kinded(mut self, kind: SymbolKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+    pub fn hidden(self) -> Self {
+        self.visible(Visibility::Hidden)
+    }
+    pub fn protected(self) -> Self {
+        self.visible(Visibility::Protected)
+    }
+    pub fn visible(mut self, visibility: Visibility) -> Self {
+        self.visibility = Some(visibility);
+        self
+    }
+    pub fn weak(mut self) -> Self {
+        self.weak = true;
+        self
+    }
+    pub fn sized(mut self, size: u64) -> Self {
+        self.size = Some(SymbolSize::Bytes(size));
+        self
+    }
+    pub fn sized_to_end(mut self) -> Self {
+        self.size = Some(SymbolSize::ToEnd);
+        self
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SymbolKind {
+    Function,
+    Data,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Visibility {
+    Default,
+    Internal,
+    Hidden,
+    Protected,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SymbolSize {
+    Bytes(u64),
+    ToEnd,
+}
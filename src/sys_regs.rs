@@ -0,0 +1,30 @@
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CReg {
+    CR0,
+    CR2,
+    CR3,
+    CR4,
+    CR8,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DReg {
+    DR0,
+    DR1,
+    DR2,
+    DR3,
+    DR4,
+    DR5,
+    DR6,
+    DR7,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SReg {
+    ES,
+    CS,
+    SS,
+    DS,
+    FS,
+    GS,
+}
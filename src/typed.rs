@@ -0,0 +1,602 @@
+use crate::Result;
+use crate::gp_regs::{RName, RSize, Reg};
+use crate::mem::Mem;
+use crate::operand::{OSize, Operand};
+use crate::writer::X86Writer;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Gpr8(pub RName);
+impl From<Gpr8> for Reg {
+    fn from(value: Gpr8) -> Self {
+        value.0 + RSize::Byte
+    }
+}
+impl From<Gpr8> for Operand<'static> {
+    fn from(value: Gpr8) -> Self {
+        Operand::Reg(value.into())
+    }
+}
+impl TryFrom<Reg> for Gpr8 {
+    type Error = Reg;
+    fn try_from(value: Reg) -> std::result::Result<Self, Self::Error> {
+        if value.size == RSize::Byte {
+            Ok(Gpr8(value.name))
+        } else {
+            Err(value)
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Gpr16(pub RName);
+impl From<Gpr16> for Reg {
+    fn from(value: Gpr16) -> Self {
+        value.0 + RSize::Word
+    }
+}
+impl From<Gpr16> for Operand<'static> {
+    fn from(value: Gpr16) -> Self {
+        Operand::Reg(value.into())
+    }
+}
+impl TryFrom<Reg> for Gpr16 {
+    type Error = Reg;
+    fn try_from(value: Reg) -> std::result::Result<Self, Self::Error> {
+        if value.size == RSize::Word {
+            Ok(Gpr16(value.name))
+        } else {
+            Err(value)
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Gpr32(pub RName);
+impl From<Gpr32> for Reg {
+    fn from(value: Gpr32) -> Self {
+        value.0 + RSize::DWord
+    }
+}
+impl From<Gpr32> for Operand<'static> {
+    fn from(value: Gpr32) -> Self {
+        Operand::Reg(value.into())
+    }
+}
+impl TryFrom<Reg> for Gpr32 {
+    type Error = Reg;
+    fn try_from(value: Reg) -> std::result::Result<Self, Self::Error> {
+        if value.size == RSize::DWord {
+            Ok(Gpr32(value.name))
+        } else {
+            Err(value)
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Gpr64(pub RName);
+impl From<Gpr64> for Reg {
+    fn from(value: Gpr64) -> Self {
+        value.0 + RSize::QWord
+    }
+}
+impl From<Gpr64> for Operand<'static> {
+    fn from(value: Gpr64) -> Self {
+        Operand::Reg(value.into())
+    }
+}
+impl TryFrom<Reg> for Gpr64 {
+    type Error = Reg;
+    fn try_from(value: Reg) -> std::result::Result<Self, Self::Error> {
+        if value.size == RSize::QWord {
+            Ok(Gpr64(value.name))
+        } else {
+            Err(value)
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct M8<'a>(pub Mem<'a>);
+impl<'a> From<M8<'a>> for Operand<'a> {
+    fn from(value: M8<'a>) -> Self {
+        Operand::Mem(value.0 + OSize::Byte)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct M16<'a>(pub Mem<'a>);
+impl<'a> From<M16<'a>> for Operand<'a> {
+    fn from(value: M16<'a>) -> Self {
+        Operand::Mem(value.0 + OSize::Word)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct M32<'a>(pub Mem<'a>);
+impl<'a> From<M32<'a>> for Operand<'a> {
+    fn from(value: M32<'a>) -> Self {
+        Operand::Mem(value.0 + OSize::DWord)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct M64<'a>(pub Mem<'a>);
+impl<'a> From<M64<'a>> for Operand<'a> {
+    fn from(value: M64<'a>) -> Self {
+        Operand::Mem(value.0 + OSize::QWord)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Imm32(pub i32);
+impl From<Imm32> for Operand<'static> {
+    fn from(value: Imm32) -> Self {
+        value.0.into()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Imm64(pub i64);
+impl From<Imm64> for Operand<'static> {
+    fn from(value: Imm64) -> Self {
+        value.0.into()
+    }
+}
+
+pub trait TypedWriter: X86Writer {
+    fn adc_m32_imm32<'a>(&mut self, rd: M32<'a>, rs: Imm32) -> Result<()> {
+        self.adc(rd, rs)
+    }
+    fn adc_m32_r32<'a>(&mut self, rd: M32<'a>, rs: Gpr32) -> Result<()> {
+        self.adc(rd, rs)
+    }
+    fn adc_m64_imm32<'a>(&mut self, rd: M64<'a>, rs: Imm32) -> Result<()> {
+        self.adc(rd, rs)
+    }
+    fn adc_m64_r64<'a>(&mut self, rd: M64<'a>, rs: Gpr64) -> Result<()> {
+        self.adc(rd, rs)
+    }
+    fn adc_r32_imm32(&mut self, rd: Gpr32, rs: Imm32) -> Result<()> {
+        self.adc(rd, rs)
+    }
+    fn adc_r32_m32<'a>(&mut self, rd: Gpr32, rs: M32<'a>) -> Result<()> {
+        self.adc(rd, rs)
+    }
+    fn adc_r32_r32(&mut self, rd: Gpr32, rs: Gpr32) -> Result<()> {
+        self.adc(rd, rs)
+    }
+    fn adc_r64_imm32(&mut self, rd: Gpr64, rs: Imm32) -> Result<()> {
+        self.adc(rd, rs)
+    }
+    fn adc_r64_m64<'a>(&mut self, rd: Gpr64, rs: M64<'a>) -> Result<()> {
+        self.adc(rd, rs)
+    }
+    fn adc_r64_r64(&mut self, rd: Gpr64, rs: Gpr64) -> Result<()> {
+        self.adc(rd, rs)
+    }
+    fn add_m32_imm32<'a>(&mut self, rd: M32<'a>, rs: Imm32) -> Result<()> {
+        self.add(rd, rs)
+    }
+    fn add_m32_r32<'a>(&mut self, rd: M32<'a>, rs: Gpr32) -> Result<()> {
+        self.add(rd, rs)
+    }
+    fn add_m64_imm32<'a>(&mut self, rd: M64<'a>, rs: Imm32) -> Result<()> {
+        self.add(rd, rs)
+    }
+    fn add_m64_r64<'a>(&mut self, rd: M64<'a>, rs: Gpr64) -> Result<()> {
+        self.add(rd, rs)
+    }
+    fn add_r32_imm32(&mut self, rd: Gpr32, rs: Imm32) -> Result<()> {
+        self.add(rd, rs)
+    }
+    fn add_r32_m32<'a>(&mut self, rd: Gpr32, rs: M32<'a>) -> Result<()> {
+        self.add(rd, rs)
+    }
+    fn add_r32_r32(&mut self, rd: Gpr32, rs: Gpr32) -> Result<()> {
+        self.add(rd, rs)
+    }
+    fn add_r64_imm32(&mut self, rd: Gpr64, rs: Imm32) -> Result<()> {
+        self.add(rd, rs)
+    }
+    fn add_r64_m64<'a>(&mut self, rd: Gpr64, rs: M64<'a>) -> Result<()> {
+        self.add(rd, rs)
+    }
+    fn add_r64_r64(&mut self, rd: Gpr64, rs: Gpr64) -> Result<()> {
+        self.add(rd, rs)
+    }
+    fn and_m32_imm32<'a>(&mut self, rd: M32<'a>, rs: Imm32) -> Result<()> {
+        self.and(rd, rs)
+    }
+    fn and_m32_r32<'a>(&mut self, rd: M32<'a>, rs: Gpr32) -> Result<()> {
+        self.and(rd, rs)
+    }
+    fn and_m64_imm32<'a>(&mut self, rd: M64<'a>, rs: Imm32) -> Result<()> {
+        self.and(rd, rs)
+    }
+    fn and_m64_r64<'a>(&mut self, rd: M64<'a>, rs: Gpr64) -> Result<()> {
+        self.and(rd, rs)
+    }
+    fn and_r32_imm32(&mut self, rd: Gpr32, rs: Imm32) -> Result<()> {
+        self.and(rd, rs)
+    }
+    fn and_r32_m32<'a>(&mut self, rd: Gpr32, rs: M32<'a>) -> Result<()> {
+        self.and(rd, rs)
+    }
+    fn and_r32_r32(&mut self, rd: Gpr32, rs: Gpr32) -> Result<()> {
+        self.and(rd, rs)
+    }
+    fn and_r64_imm32(&mut self, rd: Gpr64, rs: Imm32) -> Result<()> {
+        self.and(rd, rs)
+    }
+    fn and_r64_m64<'a>(&mut self, rd: Gpr64, rs: M64<'a>) -> Result<()> {
+        self.and(rd, rs)
+    }
+    fn and_r64_r64(&mut self, rd: Gpr64, rs: Gpr64) -> Result<()> {
+        self.and(rd, rs)
+    }
+    fn cmp_m32_imm32<'a>(&mut self, rd: M32<'a>, rs: Imm32) -> Result<()> {
+        self.cmp(rd, rs)
+    }
+    fn cmp_m32_r32<'a>(&mut self, rd: M32<'a>, rs: Gpr32) -> Result<()> {
+        self.cmp(rd, rs)
+    }
+    fn cmp_m64_imm32<'a>(&mut self, rd: M64<'a>, rs: Imm32) -> Result<()> {
+        self.cmp(rd, rs)
+    }
+    fn cmp_m64_r64<'a>(&mut self, rd: M64<'a>, rs: Gpr64) -> Result<()> {
+        self.cmp(rd, rs)
+    }
+    fn cmp_r32_imm32(&mut self, rd: Gpr32, rs: Imm32) -> Result<()> {
+        self.cmp(rd, rs)
+    }
+    fn cmp_r32_m32<'a>(&mut self, rd: Gpr32, rs: M32<'a>) -> Result<()> {
+        self.cmp(rd, rs)
+    }
+    fn cmp_r32_r32(&mut self, rd: Gpr32, rs: Gpr32) -> Result<()> {
+        self.cmp(rd, rs)
+    }
+    fn cmp_r64_imm32(&mut self, rd: Gpr64, rs: Imm32) -> Result<()> {
+        self.cmp(rd, rs)
+    }
+    fn cmp_r64_m64<'a>(&mut self, rd: Gpr64, rs: M64<'a>) -> Result<()> {
+        self.cmp(rd, rs)
+    }
+    fn cmp_r64_r64(&mut self, rd: Gpr64, rs: Gpr64) -> Result<()> {
+        self.cmp(rd, rs)
+    }
+    fn dec_m32<'a>(&mut self, rd: M32<'a>) -> Result<()> {
+        self.dec(rd)
+    }
+    fn dec_m64<'a>(&mut self, rd: M64<'a>) -> Result<()> {
+        self.dec(rd)
+    }
+    fn dec_r32(&mut self, rd: Gpr32) -> Result<()> {
+        self.dec(rd)
+    }
+    fn dec_r64(&mut self, rd: Gpr64) -> Result<()> {
+        self.dec(rd)
+    }
+    fn inc_m32<'a>(&mut self, rd: M32<'a>) -> Result<()> {
+        self.inc(rd)
+    }
+    fn inc_m64<'a>(&mut self, rd: M64<'a>) -> Result<()> {
+        self.inc(rd)
+    }
+    fn inc_r32(&mut self, rd: Gpr32) -> Result<()> {
+        self.inc(rd)
+    }
+    fn inc_r64(&mut self, rd: Gpr64) -> Result<()> {
+        self.inc(rd)
+    }
+    fn lea_r16_m<'a>(&mut self, rd: Gpr16, rs: Mem<'a>) -> Result<()> {
+        self.lea(rd.into(), rs)
+    }
+    fn lea_r32_m<'a>(&mut self, rd: Gpr32, rs: Mem<'a>) -> Result<()> {
+        self.lea(rd.into(), rs)
+    }
+    fn lea_r64_m<'a>(&mut self, rd: Gpr64, rs: Mem<'a>) -> Result<()> {
+        self.lea(rd.into(), rs)
+    }
+    fn mov_m32_imm32<'a>(&mut self, rd: M32<'a>, rs: Imm32) -> Result<()> {
+        self.mov(rd, rs)
+    }
+    fn mov_m32_r32<'a>(&mut self, rd: M32<'a>, rs: Gpr32) -> Result<()> {
+        self.mov(rd, rs)
+    }
+    fn mov_m64_imm32<'a>(&mut self, rd: M64<'a>, rs: Imm32) -> Result<()> {
+        self.mov(rd, rs)
+    }
+    fn mov_m64_r64<'a>(&mut self, rd: M64<'a>, rs: Gpr64) -> Result<()> {
+        self.mov(rd, rs)
+    }
+    fn mov_r32_imm32(&mut self, rd: Gpr32, rs: Imm32) -> Result<()> {
+        self.mov(rd, rs)
+    }
+    fn mov_r32_m32<'a>(&mut self, rd: Gpr32, rs: M32<'a>) -> Result<()> {
+        self.mov(rd, rs)
+    }
+    fn mov_r32_r32(&mut self, rd: Gpr32, rs: Gpr32) -> Result<()> {
+        self.mov(rd, rs)
+    }
+    fn mov_r64_imm32(&mut self, rd: Gpr64, rs: Imm32) -> Result<()> {
+        self.mov(rd, rs)
+    }
+    fn mov_r64_imm64(&mut self, rd: Gpr64, rs: Imm64) -> Result<()> {
+        self.mov(rd, rs)
+    }
+    fn mov_r64_m64<'a>(&mut self, rd: Gpr64, rs: M64<'a>) -> Result<()> {
+        self.mov(rd, rs)
+    }
+    fn mov_r64_r64(&mut self, rd: Gpr64, rs: Gpr64) -> Result<()> {
+        self.mov(rd, rs)
+    }
+    fn movsx_r32_m16<'a>(&mut self, rd: Gpr32, rs: M16<'a>) -> Result<()> {
+        self.movsx(rd, rs)
+    }
+    fn movsx_r32_m8<'a>(&mut self, rd: Gpr32, rs: M8<'a>) -> Result<()> {
+        self.movsx(rd, rs)
+    }
+    fn movsx_r32_r16(&mut self, rd: Gpr32, rs: Gpr16) -> Result<()> {
+        self.movsx(rd, rs)
+    }
+    fn movsx_r32_r8(&mut self, rd: Gpr32, rs: Gpr8) -> Result<()> {
+        self.movsx(rd, rs)
+    }
+    fn movsx_r64_m16<'a>(&mut self, rd: Gpr64, rs: M16<'a>) -> Result<()> {
+        self.movsx(rd, rs)
+    }
+    fn movsx_r64_m8<'a>(&mut self, rd: Gpr64, rs: M8<'a>) -> Result<()> {
+        self.movsx(rd, rs)
+    }
+    fn movsx_r64_r16(&mut self, rd: Gpr64, rs: Gpr16) -> Result<()> {
+        self.movsx(rd, rs)
+    }
+    fn movsx_r64_r8(&mut self, rd: Gpr64, rs: Gpr8) -> Result<()> {
+        self.movsx(rd, rs)
+    }
+    fn movzx_r32_m16<'a>(&mut self, rd: Gpr32, rs: M16<'a>) -> Result<()> {
+        self.movzx(rd, rs)
+    }
+    fn movzx_r32_m8<'a>(&mut self, rd: Gpr32, rs: M8<'a>) -> Result<()> {
+        self.movzx(rd, rs)
+    }
+    fn movzx_r32_r16(&mut self, rd: Gpr32, rs: Gpr16) -> Result<()> {
+        self.movzx(rd, rs)
+    }
+    fn movzx_r32_r8(&mut self, rd: Gpr32, rs: Gpr8) -> Result<()> {
+        self.movzx(rd, rs)
+    }
+    fn movzx_r64_m16<'a>(&mut self, rd: Gpr64, rs: M16<'a>) -> Result<()> {
+        self.movzx(rd, rs)
+    }
+    fn movzx_r64_m8<'a>(&mut self, rd: Gpr64, rs: M8<'a>) -> Result<()> {
+        self.movzx(rd, rs)
+    }
+    fn movzx_r64_r16(&mut self, rd: Gpr64, rs: Gpr16) -> Result<()> {
+        self.movzx(rd, rs)
+    }
+    fn movzx_r64_r8(&mut self, rd: Gpr64, rs: Gpr8) -> Result<()> {
+        self.movzx(rd, rs)
+    }
+    fn neg_m32<'a>(&mut self, rd: M32<'a>) -> Result<()> {
+        self.neg(rd)
+    }
+    fn neg_m64<'a>(&mut self, rd: M64<'a>) -> Result<()> {
+        self.neg(rd)
+    }
+    fn neg_r32(&mut self, rd: Gpr32) -> Result<()> {
+        self.neg(rd)
+    }
+    fn neg_r64(&mut self, rd: Gpr64) -> Result<()> {
+        self.neg(rd)
+    }
+    fn not_m32<'a>(&mut self, rd: M32<'a>) -> Result<()> {
+        self.not(rd)
+    }
+    fn not_m64<'a>(&mut self, rd: M64<'a>) -> Result<()> {
+        self.not(rd)
+    }
+    fn not_r32(&mut self, rd: Gpr32) -> Result<()> {
+        self.not(rd)
+    }
+    fn not_r64(&mut self, rd: Gpr64) -> Result<()> {
+        self.not(rd)
+    }
+    fn or_m32_imm32<'a>(&mut self, rd: M32<'a>, rs: Imm32) -> Result<()> {
+        self.or(rd, rs)
+    }
+    fn or_m32_r32<'a>(&mut self, rd: M32<'a>, rs: Gpr32) -> Result<()> {
+        self.or(rd, rs)
+    }
+    fn or_m64_imm32<'a>(&mut self, rd: M64<'a>, rs: Imm32) -> Result<()> {
+        self.or(rd, rs)
+    }
+    fn or_m64_r64<'a>(&mut self, rd: M64<'a>, rs: Gpr64) -> Result<()> {
+        self.or(rd, rs)
+    }
+    fn or_r32_imm32(&mut self, rd: Gpr32, rs: Imm32) -> Result<()> {
+        self.or(rd, rs)
+    }
+    fn or_r32_m32<'a>(&mut self, rd: Gpr32, rs: M32<'a>) -> Result<()> {
+        self.or(rd, rs)
+    }
+    fn or_r32_r32(&mut self, rd: Gpr32, rs: Gpr32) -> Result<()> {
+        self.or(rd, rs)
+    }
+    fn or_r64_imm32(&mut self, rd: Gpr64, rs: Imm32) -> Result<()> {
+        self.or(rd, rs)
+    }
+    fn or_r64_m64<'a>(&mut self, rd: Gpr64, rs: M64<'a>) -> Result<()> {
+        self.or(rd, rs)
+    }
+    fn or_r64_r64(&mut self, rd: Gpr64, rs: Gpr64) -> Result<()> {
+        self.or(rd, rs)
+    }
+    fn pop_m64<'a>(&mut self, rd: M64<'a>) -> Result<()> {
+        self.pop(rd)
+    }
+    fn pop_r64(&mut self, rd: Gpr64) -> Result<()> {
+        self.pop(rd)
+    }
+    fn push_imm32(&mut self, rs: Imm32) -> Result<()> {
+        self.push(rs)
+    }
+    fn push_m64<'a>(&mut self, rs: M64<'a>) -> Result<()> {
+        self.push(rs)
+    }
+    fn push_r64(&mut self, rs: Gpr64) -> Result<()> {
+        self.push(rs)
+    }
+    fn sar_m32_imm8<'a>(&mut self, rd: M32<'a>, rs: u8) -> Result<()> {
+        self.sar(rd, rs)
+    }
+    fn sar_m64_imm8<'a>(&mut self, rd: M64<'a>, rs: u8) -> Result<()> {
+        self.sar(rd, rs)
+    }
+    fn sar_r32_imm8(&mut self, rd: Gpr32, rs: u8) -> Result<()> {
+        self.sar(rd, rs)
+    }
+    fn sar_r64_imm8(&mut self, rd: Gpr64, rs: u8) -> Result<()> {
+        self.sar(rd, rs)
+    }
+    fn sbb_m32_imm32<'a>(&mut self, rd: M32<'a>, rs: Imm32) -> Result<()> {
+        self.sbb(rd, rs)
+    }
+    fn sbb_m32_r32<'a>(&mut self, rd: M32<'a>, rs: Gpr32) -> Result<()> {
+        self.sbb(rd, rs)
+    }
+    fn sbb_m64_imm32<'a>(&mut self, rd: M64<'a>, rs: Imm32) -> Result<()> {
+        self.sbb(rd, rs)
+    }
+    fn sbb_m64_r64<'a>(&mut self, rd: M64<'a>, rs: Gpr64) -> Result<()> {
+        self.sbb(rd, rs)
+    }
+    fn sbb_r32_imm32(&mut self, rd: Gpr32, rs: Imm32) -> Result<()> {
+        self.sbb(rd, rs)
+    }
+    fn sbb_r32_m32<'a>(&mut self, rd: Gpr32, rs: M32<'a>) -> Result<()> {
+        self.sbb(rd, rs)
+    }
+    fn sbb_r32_r32(&mut self, rd: Gpr32, rs: Gpr32) -> Result<()> {
+        self.sbb(rd, rs)
+    }
+    fn sbb_r64_imm32(&mut self, rd: Gpr64, rs: Imm32) -> Result<()> {
+        self.sbb(rd, rs)
+    }
+    fn sbb_r64_m64<'a>(&mut self, rd: Gpr64, rs: M64<'a>) -> Result<()> {
+        self.sbb(rd, rs)
+    }
+    fn sbb_r64_r64(&mut self, rd: Gpr64, rs: Gpr64) -> Result<()> {
+        self.sbb(rd, rs)
+    }
+    fn shl_m32_imm8<'a>(&mut self, rd: M32<'a>, rs: u8) -> Result<()> {
+        self.shl(rd, rs)
+    }
+    fn shl_m64_imm8<'a>(&mut self, rd: M64<'a>, rs: u8) -> Result<()> {
+        self.shl(rd, rs)
+    }
+    fn shl_r32_imm8(&mut self, rd: Gpr32, rs: u8) -> Result<()> {
+        self.shl(rd, rs)
+    }
+    fn shl_r64_imm8(&mut self, rd: Gpr64, rs: u8) -> Result<()> {
+        self.shl(rd, rs)
+    }
+    fn shr_m32_imm8<'a>(&mut self, rd: M32<'a>, rs: u8) -> Result<()> {
+        self.shr(rd, rs)
+    }
+    fn shr_m64_imm8<'a>(&mut self, rd: M64<'a>, rs: u8) -> Result<()> {
+        self.shr(rd, rs)
+    }
+    fn shr_r32_imm8(&mut self, rd: Gpr32, rs: u8) -> Result<()> {
+        self.shr(rd, rs)
+    }
+    fn shr_r64_imm8(&mut self, rd: Gpr64, rs: u8) -> Result<()> {
+        self.shr(rd, rs)
+    }
+    fn sub_m32_imm32<'a>(&mut self, rd: M32<'a>, rs: Imm32) -> Result<()> {
+        self.sub(rd, rs)
+    }
+    fn sub_m32_r32<'a>(&mut self, rd: M32<'a>, rs: Gpr32) -> Result<()> {
+        self.sub(rd, rs)
+    }
+    fn sub_m64_imm32<'a>(&mut self, rd: M64<'a>, rs: Imm32) -> Result<()> {
+        self.sub(rd, rs)
+    }
+    fn sub_m64_r64<'a>(&mut self, rd: M64<'a>, rs: Gpr64) -> Result<()> {
+        self.sub(rd, rs)
+    }
+    fn sub_r32_imm32(&mut self, rd: Gpr32, rs: Imm32) -> Result<()> {
+        self.sub(rd, rs)
+    }
+    fn sub_r32_m32<'a>(&mut self, rd: Gpr32, rs: M32<'a>) -> Result<()> {
+        self.sub(rd, rs)
+    }
+    fn sub_r32_r32(&mut self, rd: Gpr32, rs: Gpr32) -> Result<()> {
+        self.sub(rd, rs)
+    }
+    fn sub_r64_imm32(&mut self, rd: Gpr64, rs: Imm32) -> Result<()> {
+        self.sub(rd, rs)
+    }
+    fn sub_r64_m64<'a>(&mut self, rd: Gpr64, rs: M64<'a>) -> Result<()> {
+        self.sub(rd, rs)
+    }
+    fn sub_r64_r64(&mut self, rd: Gpr64, rs: Gpr64) -> Result<()> {
+        self.sub(rd, rs)
+    }
+    fn test_m32_imm32<'a>(&mut self, rd: M32<'a>, rs: Imm32) -> Result<()> {
+        self.test(rd, rs)
+    }
+    fn test_m32_r32<'a>(&mut self, rd: M32<'a>, rs: Gpr32) -> Result<()> {
+        self.test(rd, rs)
+    }
+    fn test_m64_imm32<'a>(&mut self, rd: M64<'a>, rs: Imm32) -> Result<()> {
+        self.test(rd, rs)
+    }
+    fn test_m64_r64<'a>(&mut self, rd: M64<'a>, rs: Gpr64) -> Result<()> {
+        self.test(rd, rs)
+    }
+    fn test_r32_imm32(&mut self, rd: Gpr32, rs: Imm32) -> Result<()> {
+        self.test(rd, rs)
+    }
+    fn test_r32_r32(&mut self, rd: Gpr32, rs: Gpr32) -> Result<()> {
+        self.test(rd, rs)
+    }
+    fn test_r64_imm32(&mut self, rd: Gpr64, rs: Imm32) -> Result<()> {
+        self.test(rd, rs)
+    }
+    fn test_r64_r64(&mut self, rd: Gpr64, rs: Gpr64) -> Result<()> {
+        self.test(rd, rs)
+    }
+    fn xor_m32_imm32<'a>(&mut self, rd: M32<'a>, rs: Imm32) -> Result<()> {
+        self.xor(rd, rs)
+    }
+    fn xor_m32_r32<'a>(&mut self, rd: M32<'a>, rs: Gpr32) -> Result<()> {
+        self.xor(rd, rs)
+    }
+    fn xor_m64_imm32<'a>(&mut self, rd: M64<'a>, rs: Imm32) -> Result<()> {
+        self.xor(rd, rs)
+    }
+    fn xor_m64_r64<'a>(&mut self, rd: M64<'a>, rs: Gpr64) -> Result<()> {
+        self.xor(rd, rs)
+    }
+    fn xor_r32_imm32(&mut self, rd: Gpr32, rs: Imm32) -> Result<()> {
+        self.xor(rd, rs)
+    }
+    fn xor_r32_m32<'a>(&mut self, rd: Gpr32, rs: M32<'a>) -> Result<()> {
+        self.xor(rd, rs)
+    }
+    fn xor_r32_r32(&mut self, rd: Gpr32, rs: Gpr32) -> Result<()> {
+        self.xor(rd, rs)
+    }
+    fn xor_r64_imm32(&mut self, rd: Gpr64, rs: Imm32) -> Result<()> {
+        self.xor(rd, rs)
+    }
+    fn xor_r64_m64<'a>(&mut self, rd: Gpr64, rs: M64<'a>) -> Result<()> {
+        self.xor(rd, rs)
+    }
+    fn xor_r64_r64(&mut self, rd: Gpr64, rs: Gpr64) -> Result<()> {
+        self.xor(rd, rs)
+    }
+}
+impl<W: X86Writer> TypedWriter for W {}
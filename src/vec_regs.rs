@@ -0,0 +1,209 @@
+use std::ops::Add;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VReg {
+    pub name: VName,
+    pub size: VSize,
+}
+impl VReg {
+    pub fn with_name(mut self, name: VName) -> VReg {
+        self.name = name;
+        self
+    }
+    pub fn with_size(mut self, size: VSize) -> VReg {
+        self.size = size;
+        self
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum VName {
+    V0,
+    V1,
+    V2,
+    V3,
+    V4,
+    V5,
+    V6,
+    V7,
+    V8,
+    V9,
+    V10,
+    V11,
+    V12,
+    V13,
+    V14,
+    V15,
+}
+impl VName {
+    pub fn with_size(self, size: VSize) -> VReg {
+        VReg { name: self, size }
+    }
+}
+impl Add<VSize> for VName {
+    type Output = VReg;
+    fn add(self, rhs: VSize) -> Self::Output {
+        self.with_size(rhs)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum VSize {
+    Xmm,
+    Ymm,
+}
+impl VSize {
+    pub fn with_name(self, name: VName) -> VReg {
+        VReg { name, size: self }
+    }
+}
+impl Add<VName> for VSize {
+    type Output = VReg;
+    fn add(self, rhs: VName) -> Self::Output {
+        self.with_name(rhs)
+    }
+}
+
+pub const XMM0: VReg = VReg {
+    name: VName::V0,
+    size: VSize::Xmm,
+};
+pub const YMM0: VReg = VReg {
+    name: VName::V0,
+    size: VSize::Ymm,
+};
+
+pub const XMM1: VReg = VReg {
+    name: VName::V1,
+    size: VSize::Xmm,
+};
+pub const YMM1: VReg = VReg {
+    name: VName::V1,
+    size: VSize::Ymm,
+};
+
+pub const XMM2: VReg = VReg {
+    name: VName::V2,
+    size: VSize::Xmm,
+};
+pub const YMM2: VReg = VReg {
+    name: VName::V2,
+    size: VSize::Ymm,
+};
+
+pub const XMM3: VReg = VReg {
+    name: VName::V3,
+    size: VSize::Xmm,
+};
+pub const YMM3: VReg = VReg {
+    name: VName::V3,
+    size: VSize::Ymm,
+};
+
+pub const XMM4: VReg = VReg {
+    name: VName::V4,
+    size: VSize::Xmm,
+};
+pub const YMM4: VReg = VReg {
+    name: VName::V4,
+    size: VSize::Ymm,
+};
+
+pub const XMM5: VReg = VReg {
+    name: VName::V5,
+    size: VSize::Xmm,
+};
+pub const YMM5: VReg = VReg {
+    name: VName::V5,
+    size: VSize::Ymm,
+};
+
+pub const XMM6: VReg = VReg {
+    name: VName::V6,
+    size: VSize::Xmm,
+};
+pub const YMM6: VReg = VReg {
+    name: VName::V6,
+    size: VSize::Ymm,
+};
+
+pub const XMM7: VReg = VReg {
+    name: VName::V7,
+    size: VSize::Xmm,
+};
+pub const YMM7: VReg = VReg {
+    name: VName::V7,
+    size: VSize::Ymm,
+};
+
+pub const XMM8: VReg = VReg {
+    name: VName::V8,
+    size: VSize::Xmm,
+};
+pub const YMM8: VReg = VReg {
+    name: VName::V8,
+    size: VSize::Ymm,
+};
+
+pub const XMM9: VReg = VReg {
+    name: VName::V9,
+    size: VSize::Xmm,
+};
+pub const YMM9: VReg = VReg {
+    name: VName::V9,
+    size: VSize::Ymm,
+};
+
+pub const XMM10: VReg = VReg {
+    name: VName::V10,
+    size: VSize::Xmm,
+};
+pub const YMM10: VReg = VReg {
+    name: VName::V10,
+    size: VSize::Ymm,
+};
+
+pub const XMM11: VReg = VReg {
+    name: VName::V11,
+    size: VSize::Xmm,
+};
+pub const YMM11: VReg = VReg {
+    name: VName::V11,
+    size: VSize::Ymm,
+};
+
+pub const XMM12: VReg = VReg {
+    name: VName::V12,
+    size: VSize::Xmm,
+};
+pub const YMM12: VReg = VReg {
+    name: VName::V12,
+    size: VSize::Ymm,
+};
+
+pub const XMM13: VReg = VReg {
+    name: VName::V13,
+    size: VSize::Xmm,
+};
+pub const YMM13: VReg = VReg {
+    name: VName::V13,
+    size: VSize::Ymm,
+};
+
+pub const XMM14: VReg = VReg {
+    name: VName::V14,
+    size: VSize::Xmm,
+};
+pub const YMM14: VReg = VReg {
+    name: VName::V14,
+    size: VSize::Ymm,
+};
+
+pub const XMM15: VReg = VReg {
+    name: VName::V15,
+    size: VSize::Xmm,
+};
+pub const YMM15: VReg = VReg {
+    name: VName::V15,
+    size: VSize::Ymm,
+};
@@ -1,164 +1,496 @@
-use std::io;
-
-use crate::{gp_regs::Reg, mem::Mem, operand::Operand};
+use crate::{
+    Result, data::Data, expr::Expr, gp_regs::Reg, label::Label, mem::Mem, operand::Operand,
+    symbol::Symbol, vec_regs::VReg,
+};
 
 pub trait X86Writer {
-    fn emit_preamble(&mut self) -> io::Result<()>;
-    fn label(&mut self, label: &str) -> io::Result<()>;
-    fn global(&mut self, label: &str) -> io::Result<()>;
-    fn text(&mut self) -> io::Result<()>;
-    fn rodata(&mut self) -> io::Result<()>;
-    fn blank(&mut self) -> io::Result<()>;
-    fn comment(&mut self, comment: &str) -> io::Result<()>;
+    fn emit_preamble(&mut self) -> Result<()>;
+    fn bits(&mut self, bits: Bits) -> Result<()>;
+    fn label(&mut self, label: &str) -> Result<()>;
+    fn new_label(&mut self) -> Label;
+    fn bind(&mut self, label: &Label) -> Result<()>;
+    fn is_bound(&self, label: &Label) -> bool;
+    fn begin_function(&mut self, name: &str) -> Result<()>;
+    fn end_function(&mut self) -> Result<()>;
+    fn new_local_label(&mut self) -> Label;
+    fn global(&mut self, label: &str) -> Result<()>;
+    fn global_symbol(&mut self, symbol: &Symbol) -> Result<()>;
+    fn r#extern(&mut self, label: &str) -> Result<()>;
+    fn extern_weak(&mut self, label: &str) -> Result<()>;
+    fn text(&mut self) -> Result<()>;
+    fn rodata(&mut self) -> Result<()>;
+    fn blank(&mut self) -> Result<()>;
+    fn comment(&mut self, comment: &str) -> Result<()>;
+    fn finish(&mut self) -> Result<()>;
 
-    fn db(&mut self, label: &str, bytes: &[&[u8]]) -> io::Result<()>;
-    fn equ(&mut self, label: &str, value: i64) -> io::Result<()>;
+    fn db(&mut self, label: &str, bytes: &[&[u8]]) -> Result<()>;
+    fn dw(&mut self, label: &str, values: &[Data]) -> Result<()>;
+    fn dd(&mut self, label: &str, values: &[Data]) -> Result<()>;
+    fn dq(&mut self, label: &str, values: &[Data]) -> Result<()>;
+    fn dt(&mut self, label: &str, values: &[Data]) -> Result<()>;
+    fn r#do(&mut self, label: &str, values: &[Data]) -> Result<()>;
+    fn equ(&mut self, label: &str, value: i64) -> Result<()>;
+    fn equ_expr(&mut self, label: &str, value: &Expr) -> Result<()>;
 
     fn adc<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> io::Result<()>;
+    ) -> Result<()>;
+    fn adc3<'a, 'b, 'c>(
+        &mut self,
+        rd: impl Into<Operand<'a>>,
+        rs0: impl Into<Operand<'b>>,
+        rs1: impl Into<Operand<'c>>,
+    ) -> Result<()>;
     fn add<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> io::Result<()>;
+    ) -> Result<()>;
+    fn add3<'a, 'b, 'c>(
+        &mut self,
+        rd: impl Into<Operand<'a>>,
+        rs0: impl Into<Operand<'b>>,
+        rs1: impl Into<Operand<'c>>,
+    ) -> Result<()>;
+    fn aesdec<'a>(&mut self, rd: VReg, rs: impl Into<Operand<'a>>) -> Result<()>;
+    fn aesdeclast<'a>(&mut self, rd: VReg, rs: impl Into<Operand<'a>>) -> Result<()>;
+    fn aesenc<'a>(&mut self, rd: VReg, rs: impl Into<Operand<'a>>) -> Result<()>;
+    fn aesenclast<'a>(&mut self, rd: VReg, rs: impl Into<Operand<'a>>) -> Result<()>;
+    fn aesimc<'a>(&mut self, rd: VReg, rs: impl Into<Operand<'a>>) -> Result<()>;
+    fn aeskeygenassist<'a>(
+        &mut self,
+        rd: VReg,
+        rs: impl Into<Operand<'a>>,
+        imm: u8,
+    ) -> Result<()>;
     fn and<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> io::Result<()>;
-    fn call<'a>(&mut self, rs: impl Into<Operand<'a>>) -> io::Result<()>;
-    fn cdq(&mut self) -> io::Result<()>;
-    fn clc(&mut self) -> io::Result<()>;
-    fn cld(&mut self) -> io::Result<()>;
-    fn cli(&mut self) -> io::Result<()>;
+    ) -> Result<()>;
+    fn and3<'a, 'b, 'c>(
+        &mut self,
+        rd: impl Into<Operand<'a>>,
+        rs0: impl Into<Operand<'b>>,
+        rs1: impl Into<Operand<'c>>,
+    ) -> Result<()>;
+    fn bt<'a, 'b>(
+        &mut self,
+        rd: impl Into<Operand<'a>>,
+        rs: impl Into<Operand<'b>>,
+    ) -> Result<()>;
+    fn call<'a>(&mut self, rs: impl Into<Operand<'a>>) -> Result<()>;
+    fn call_far<'a>(&mut self, rs: impl Into<Operand<'a>>) -> Result<()>;
+    fn cdq(&mut self) -> Result<()>;
+    fn clc(&mut self) -> Result<()>;
+    fn cld(&mut self) -> Result<()>;
+    fn cli(&mut self) -> Result<()>;
+    fn clwb<'a>(&mut self, rs: Mem<'a>) -> Result<()>;
+    fn clflush<'a>(&mut self, rs: Mem<'a>) -> Result<()>;
+    fn clflushopt<'a>(&mut self, rs: Mem<'a>) -> Result<()>;
     fn cmp<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> io::Result<()>;
+    ) -> Result<()>;
     fn cmov<'a, 'b>(
         &mut self,
         cc: Condition,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> io::Result<()>;
-    fn cqo(&mut self) -> io::Result<()>;
-    fn cwd(&mut self) -> io::Result<()>;
-    fn dec<'a>(&mut self, rd: impl Into<Operand<'a>>) -> io::Result<()>;
-    fn div<'a>(&mut self, rs: impl Into<Operand<'a>>) -> io::Result<()>;
-    fn idiv<'a>(&mut self, rs: impl Into<Operand<'a>>) -> io::Result<()>;
-    fn imul1<'a, 'b>(&mut self, rd: impl Into<Operand<'a>>) -> io::Result<()>;
+    ) -> Result<()>;
+    fn cqo(&mut self) -> Result<()>;
+    fn cpuid(&mut self) -> Result<()>;
+    fn crc32<'a>(&mut self, rd: Reg, rs: impl Into<Operand<'a>>) -> Result<()>;
+    fn cwd(&mut self) -> Result<()>;
+    fn dec<'a>(&mut self, rd: impl Into<Operand<'a>>) -> Result<()>;
+    fn dec2<'a, 'b>(
+        &mut self,
+        rd: impl Into<Operand<'a>>,
+        rs: impl Into<Operand<'b>>,
+    ) -> Result<()>;
+    fn div<'a>(&mut self, rs: impl Into<Operand<'a>>) -> Result<()>;
+    fn endbr32(&mut self) -> Result<()>;
+    fn endbr64(&mut self) -> Result<()>;
+    fn enter(&mut self, alloc: u16, nesting: u8) -> Result<()>;
+    fn hlt(&mut self) -> Result<()>;
+    fn idiv<'a>(&mut self, rs: impl Into<Operand<'a>>) -> Result<()>;
+    fn imul1<'a, 'b>(&mut self, rd: impl Into<Operand<'a>>) -> Result<()>;
     fn imul2<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> io::Result<()>;
+    ) -> Result<()>;
     fn imul3<'a, 'b, 'c>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs0: impl Into<Operand<'b>>,
         rs1: impl Into<Operand<'c>>,
-    ) -> io::Result<()>;
-    fn inc<'a>(&mut self, rd: impl Into<Operand<'a>>) -> io::Result<()>;
-    fn jcc<'a>(&mut self, cc: Condition, rs: impl Into<Operand<'a>>) -> io::Result<()>;
-    fn jmp<'a>(&mut self, rs: impl Into<Operand<'a>>) -> io::Result<()>;
-    fn lea<'a>(&mut self, rd: Reg, rs: Mem<'a>) -> io::Result<()>;
+    ) -> Result<()>;
+    fn r#in<'a>(&mut self, rd: Reg, port: impl Into<Operand<'a>>) -> Result<()>;
+    fn inc<'a>(&mut self, rd: impl Into<Operand<'a>>) -> Result<()>;
+    fn inc2<'a, 'b>(
+        &mut self,
+        rd: impl Into<Operand<'a>>,
+        rs: impl Into<Operand<'b>>,
+    ) -> Result<()>;
+    fn incsspq(&mut self, rs: Reg) -> Result<()>;
+    fn int(&mut self, vector: u8) -> Result<()>;
+    fn int3(&mut self) -> Result<()>;
+    fn into_(&mut self) -> Result<()>;
+    fn invlpg<'a>(&mut self, rs: Mem<'a>) -> Result<()>;
+    fn iretq(&mut self) -> Result<()>;
+    fn jcc<'a>(&mut self, cc: Condition, rs: impl Into<Operand<'a>>) -> Result<()>;
+    fn jmp<'a>(&mut self, rs: impl Into<Operand<'a>>) -> Result<()>;
+    fn jmp_far<'a>(&mut self, rs: impl Into<Operand<'a>>) -> Result<()>;
+    fn lea<'a>(&mut self, rd: Reg, rs: Mem<'a>) -> Result<()>;
+    fn leave(&mut self) -> Result<()>;
+    fn lfence(&mut self) -> Result<()>;
+    fn lgdt<'a>(&mut self, rs: Mem<'a>) -> Result<()>;
+    fn lidt<'a>(&mut self, rs: Mem<'a>) -> Result<()>;
+    fn lldt<'a>(&mut self, rs: impl Into<Operand<'a>>) -> Result<()>;
+    fn r#loop<'a>(&mut self, rs: impl Into<Operand<'a>>) -> Result<()>;
+    fn loope<'a>(&mut self, rs: impl Into<Operand<'a>>) -> Result<()>;
+    fn loopne<'a>(&mut self, rs: impl Into<Operand<'a>>) -> Result<()>;
+    fn ltr<'a>(&mut self, rs: impl Into<Operand<'a>>) -> Result<()>;
     fn mov<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> io::Result<()>;
+    ) -> Result<()>;
+    fn movntdq<'a>(&mut self, rd: Mem<'a>, rs: VReg) -> Result<()>;
+    fn movnti<'a>(&mut self, rd: Mem<'a>, rs: Reg) -> Result<()>;
+    fn movntps<'a>(&mut self, rd: Mem<'a>, rs: VReg) -> Result<()>;
     fn movsx<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> io::Result<()>;
+    ) -> Result<()>;
+    fn movsxd<'a, 'b>(
+        &mut self,
+        rd: impl Into<Operand<'a>>,
+        rs: impl Into<Operand<'b>>,
+    ) -> Result<()>;
     fn movzx<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> io::Result<()>;
-    fn mul<'a>(&mut self, rs: impl Into<Operand<'a>>) -> io::Result<()>;
-    fn neg<'a>(&mut self, rd: impl Into<Operand<'a>>) -> io::Result<()>;
-    fn not<'a>(&mut self, rd: impl Into<Operand<'a>>) -> io::Result<()>;
-    fn nop(&mut self) -> io::Result<()>;
-    fn nop1<'a>(&mut self, rs: impl Into<Operand<'a>>) -> io::Result<()>;
+    ) -> Result<()>;
+    fn mfence(&mut self) -> Result<()>;
+    fn mul<'a>(&mut self, rs: impl Into<Operand<'a>>) -> Result<()>;
+    fn neg<'a>(&mut self, rd: impl Into<Operand<'a>>) -> Result<()>;
+    fn neg2<'a, 'b>(
+        &mut self,
+        rd: impl Into<Operand<'a>>,
+        rs: impl Into<Operand<'b>>,
+    ) -> Result<()>;
+    fn not<'a>(&mut self, rd: impl Into<Operand<'a>>) -> Result<()>;
+    fn not2<'a, 'b>(
+        &mut self,
+        rd: impl Into<Operand<'a>>,
+        rs: impl Into<Operand<'b>>,
+    ) -> Result<()>;
+    fn nop(&mut self) -> Result<()>;
+    fn nop1<'a>(&mut self, rs: impl Into<Operand<'a>>) -> Result<()>;
     fn or<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> io::Result<()>;
-    fn pop<'a>(&mut self, rd: impl Into<Operand<'a>>) -> io::Result<()>;
-    fn push<'a>(&mut self, rs: impl Into<Operand<'a>>) -> io::Result<()>;
+    ) -> Result<()>;
+    fn or3<'a, 'b, 'c>(
+        &mut self,
+        rd: impl Into<Operand<'a>>,
+        rs0: impl Into<Operand<'b>>,
+        rs1: impl Into<Operand<'c>>,
+    ) -> Result<()>;
+    fn out<'a>(&mut self, port: impl Into<Operand<'a>>, rs: Reg) -> Result<()>;
+    fn pause(&mut self) -> Result<()>;
+    fn pclmulqdq<'a>(&mut self, rd: VReg, rs: impl Into<Operand<'a>>, imm: u8) -> Result<()>;
+    fn pop<'a>(&mut self, rd: impl Into<Operand<'a>>) -> Result<()>;
+    fn pop2(&mut self, rd0: Reg, rd1: Reg) -> Result<()>;
+    fn prefetchnta<'a>(&mut self, rs: Mem<'a>) -> Result<()>;
+    fn prefetcht0<'a>(&mut self, rs: Mem<'a>) -> Result<()>;
+    fn prefetcht1<'a>(&mut self, rs: Mem<'a>) -> Result<()>;
+    fn prefetcht2<'a>(&mut self, rs: Mem<'a>) -> Result<()>;
+    fn prefetchw<'a>(&mut self, rs: Mem<'a>) -> Result<()>;
+    fn push<'a>(&mut self, rs: impl Into<Operand<'a>>) -> Result<()>;
+    fn push2(&mut self, rs0: Reg, rs1: Reg) -> Result<()>;
     fn rcl<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> io::Result<()>;
+    ) -> Result<()>;
     fn rcr<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> io::Result<()>;
-    fn ret(&mut self) -> io::Result<()>;
+    ) -> Result<()>;
+    fn rdmsr(&mut self) -> Result<()>;
+    fn rdpid(&mut self, rd: Reg) -> Result<()>;
+    fn rdrand(&mut self, rd: Reg) -> Result<()>;
+    fn rdseed(&mut self, rd: Reg) -> Result<()>;
+    fn rdsspq(&mut self, rd: Reg) -> Result<()>;
+    fn rdtsc(&mut self) -> Result<()>;
+    fn rdtscp(&mut self) -> Result<()>;
+    fn ret(&mut self) -> Result<()>;
+    fn ret1(&mut self, pop: u16) -> Result<()>;
+    fn retf(&mut self) -> Result<()>;
+    fn retf1(&mut self, pop: u16) -> Result<()>;
     fn rol<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> io::Result<()>;
+    ) -> Result<()>;
     fn ror<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> io::Result<()>;
+    ) -> Result<()>;
+    fn rstorssp<'a>(&mut self, rs: Mem<'a>) -> Result<()>;
     fn sal<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> io::Result<()>;
+    ) -> Result<()>;
     fn sar<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> io::Result<()>;
+    ) -> Result<()>;
+    fn saveprevssp(&mut self) -> Result<()>;
     fn sbb<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> io::Result<()>;
-    fn setcc<'a>(&mut self, cc: Condition, dst: impl Into<Operand<'a>>) -> io::Result<()>;
+    ) -> Result<()>;
+    fn sbb3<'a, 'b, 'c>(
+        &mut self,
+        rd: impl Into<Operand<'a>>,
+        rs0: impl Into<Operand<'b>>,
+        rs1: impl Into<Operand<'c>>,
+    ) -> Result<()>;
+    fn serialize(&mut self) -> Result<()>;
+    fn setcc<'a>(&mut self, cc: Condition, dst: impl Into<Operand<'a>>) -> Result<()>;
+    fn sfence(&mut self) -> Result<()>;
+    fn sgdt<'a>(&mut self, rd: Mem<'a>) -> Result<()>;
+    fn sha1msg1<'a>(&mut self, rd: VReg, rs: impl Into<Operand<'a>>) -> Result<()>;
+    fn sha1msg2<'a>(&mut self, rd: VReg, rs: impl Into<Operand<'a>>) -> Result<()>;
+    fn sha1nexte<'a>(&mut self, rd: VReg, rs: impl Into<Operand<'a>>) -> Result<()>;
+    fn sha1rnds4<'a>(&mut self, rd: VReg, rs: impl Into<Operand<'a>>, imm: u8) -> Result<()>;
+    fn sha256msg1<'a>(&mut self, rd: VReg, rs: impl Into<Operand<'a>>) -> Result<()>;
+    fn sha256msg2<'a>(&mut self, rd: VReg, rs: impl Into<Operand<'a>>) -> Result<()>;
+    fn sha256rnds2<'a>(&mut self, rd: VReg, rs: impl Into<Operand<'a>>) -> Result<()>;
     fn shl<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> io::Result<()>;
+    ) -> Result<()>;
     fn shr<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> io::Result<()>;
-    fn stc(&mut self) -> io::Result<()>;
-    fn std(&mut self) -> io::Result<()>;
-    fn sti(&mut self) -> io::Result<()>;
+    ) -> Result<()>;
+    fn sidt<'a>(&mut self, rd: Mem<'a>) -> Result<()>;
+    fn stc(&mut self) -> Result<()>;
+    fn std(&mut self) -> Result<()>;
+    fn sti(&mut self) -> Result<()>;
     fn sub<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> io::Result<()>;
-    fn syscall(&mut self) -> io::Result<()>;
+    ) -> Result<()>;
+    fn sub3<'a, 'b, 'c>(
+        &mut self,
+        rd: impl Into<Operand<'a>>,
+        rs0: impl Into<Operand<'b>>,
+        rs1: impl Into<Operand<'c>>,
+    ) -> Result<()>;
+    fn swapgs(&mut self) -> Result<()>;
+    fn syscall(&mut self) -> Result<()>;
+    fn sysexit(&mut self) -> Result<()>;
+    fn sysret(&mut self) -> Result<()>;
+    fn sysretq(&mut self) -> Result<()>;
     fn test<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> io::Result<()>;
+    ) -> Result<()>;
+    fn ud2(&mut self) -> Result<()>;
+    fn vcvtph2ps<'a>(&mut self, rd: VReg, rs: impl Into<Operand<'a>>) -> Result<()>;
+    fn vcvtps2ph<'a>(&mut self, rd: impl Into<Operand<'a>>, rs: VReg, imm: u8) -> Result<()>;
+    fn vfmadd132<'a>(
+        &mut self,
+        ty: FpType,
+        dst_a: VReg,
+        c: VReg,
+        b: impl Into<Operand<'a>>,
+    ) -> Result<()>;
+    fn vfmadd213<'a>(
+        &mut self,
+        ty: FpType,
+        dst_b: VReg,
+        a: VReg,
+        c: impl Into<Operand<'a>>,
+    ) -> Result<()>;
+    fn vfmadd231<'a>(
+        &mut self,
+        ty: FpType,
+        dst_c: VReg,
+        a: VReg,
+        b: impl Into<Operand<'a>>,
+    ) -> Result<()>;
+    fn vfmaddsub132<'a>(
+        &mut self,
+        ty: FpType,
+        dst_a: VReg,
+        c: VReg,
+        b: impl Into<Operand<'a>>,
+    ) -> Result<()>;
+    fn vfmaddsub213<'a>(
+        &mut self,
+        ty: FpType,
+        dst_b: VReg,
+        a: VReg,
+        c: impl Into<Operand<'a>>,
+    ) -> Result<()>;
+    fn vfmaddsub231<'a>(
+        &mut self,
+        ty: FpType,
+        dst_c: VReg,
+        a: VReg,
+        b: impl Into<Operand<'a>>,
+    ) -> Result<()>;
+    fn vfmsub132<'a>(
+        &mut self,
+        ty: FpType,
+        dst_a: VReg,
+        c: VReg,
+        b: impl Into<Operand<'a>>,
+    ) -> Result<()>;
+    fn vfmsub213<'a>(
+        &mut self,
+        ty: FpType,
+        dst_b: VReg,
+        a: VReg,
+        c: impl Into<Operand<'a>>,
+    ) -> Result<()>;
+    fn vfmsub231<'a>(
+        &mut self,
+        ty: FpType,
+        dst_c: VReg,
+        a: VReg,
+        b: impl Into<Operand<'a>>,
+    ) -> Result<()>;
+    fn vfmsubadd132<'a>(
+        &mut self,
+        ty: FpType,
+        dst_a: VReg,
+        c: VReg,
+        b: impl Into<Operand<'a>>,
+    ) -> Result<()>;
+    fn vfmsubadd213<'a>(
+        &mut self,
+        ty: FpType,
+        dst_b: VReg,
+        a: VReg,
+        c: impl Into<Operand<'a>>,
+    ) -> Result<()>;
+    fn vfmsubadd231<'a>(
+        &mut self,
+        ty: FpType,
+        dst_c: VReg,
+        a: VReg,
+        b: impl Into<Operand<'a>>,
+    ) -> Result<()>;
+    fn vfnmadd132<'a>(
+        &mut self,
+        ty: FpType,
+        dst_a: VReg,
+        c: VReg,
+        b: impl Into<Operand<'a>>,
+    ) -> Result<()>;
+    fn vfnmadd213<'a>(
+        &mut self,
+        ty: FpType,
+        dst_b: VReg,
+        a: VReg,
+        c: impl Into<Operand<'a>>,
+    ) -> Result<()>;
+    fn vfnmadd231<'a>(
+        &mut self,
+        ty: FpType,
+        dst_c: VReg,
+        a: VReg,
+        b: impl Into<Operand<'a>>,
+    ) -> Result<()>;
+    fn vfnmsub132<'a>(
+        &mut self,
+        ty: FpType,
+        dst_a: VReg,
+        c: VReg,
+        b: impl Into<Operand<'a>>,
+    ) -> Result<()>;
+    fn vfnmsub213<'a>(
+        &mut self,
+        ty: FpType,
+        dst_b: VReg,
+        a: VReg,
+        c: impl Into<Operand<'a>>,
+    ) -> Result<()>;
+    fn vfnmsub231<'a>(
+        &mut self,
+        ty: FpType,
+        dst_c: VReg,
+        a: VReg,
+        b: impl Into<Operand<'a>>,
+    ) -> Result<()>;
+    fn wbinvd(&mut self) -> Result<()>;
+    fn wrmsr(&mut self) -> Result<()>;
+    fn xgetbv(&mut self) -> Result<()>;
     fn xor<'a, 'b>(
         &mut self,
         rd: impl Into<Operand<'a>>,
         rs: impl Into<Operand<'b>>,
-    ) -> io::Result<()>;
+    ) -> Result<()>;
+    fn xor3<'a, 'b, 'c>(
+        &mut self,
+        rd: impl Into<Operand<'a>>,
+        rs0: impl Into<Operand<'b>>,
+        rs1: impl Into<Operand<'c>>,
+    ) -> Result<()>;
+    fn xrstor<'a>(&mut self, rs: Mem<'a>) -> Result<()>;
+    fn xrstor64<'a>(&mut self, rs: Mem<'a>) -> Result<()>;
+    fn xsave<'a>(&mut self, rd: Mem<'a>) -> Result<()>;
+    fn xsave64<'a>(&mut self, rd: Mem<'a>) -> Result<()>;
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Bits {
+    Bits16,
+    Bits32,
+    Bits64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Feature {
+    Apx,
+    Retpoline,
+    NarrowMovImm,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FpType {
+    PS,
+    PD,
+    SS,
+    SD,
+}
+impl FpType {
+    pub fn is_packed(self) -> bool {
+        matches!(self, FpType::PS | FpType::PD)
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
@@ -228,4 +560,8 @@ impl Condition {
             _ => self,
         }
     }
+
+    pub fn is_count_test(self) -> bool {
+        matches!(self, Condition::CXZ | Condition::ECXZ | Condition::RCXZ)
+    }
 }
@@ -0,0 +1,27 @@
+#![allow(dead_code)]
+
+use gen86::{
+    Error, Result,
+    error::{Invalid, LabelError},
+    nasm::NasmWriter,
+};
+
+pub fn emit(f: impl FnOnce(&mut NasmWriter<&mut Vec<u8>>) -> Result<()>) -> Result<String> {
+    let mut out = Vec::new();
+    f(&mut NasmWriter::new(&mut out))?;
+    Ok(String::from_utf8(out).unwrap())
+}
+
+pub fn invalid(result: Result<String>) -> Invalid {
+    match result {
+        Err(Error::Invalid(invalid)) => invalid,
+        other => panic!("expected an invalid instruction, got {other:?}"),
+    }
+}
+
+pub fn label_errors(result: Result<String>) -> Vec<LabelError> {
+    match result {
+        Err(Error::Labels(errs)) => errs,
+        other => panic!("expected label errors, got {other:?}"),
+    }
+}
@@ -0,0 +1,46 @@
+mod common;
+
+use common::{emit, invalid};
+use gen86::{data::Data, error::InvalidKind, expr::Expr, writer::X86Writer};
+
+#[test]
+fn floats_use_nasm_syntax() {
+    let values = [
+        Data::from(1.0e-7),
+        f64::INFINITY.into(),
+        f64::NEG_INFINITY.into(),
+        f64::NAN.into(),
+        2.5.into(),
+        1e300.into(),
+    ];
+    let out = emit(|w| w.dq("t", &values)).unwrap();
+    assert_eq!(
+        out,
+        "t dq 1.0e-7, __Infinity__, -__Infinity__, __QNaN__, 2.5, 1.0e300\n"
+    );
+}
+
+#[test]
+fn mixed_values() {
+    let values = [Data::from(1.5f32), (-1i64).into(), 0xFFFF_FFFFu64.into()];
+    assert_eq!(
+        emit(|w| w.dd("u", &values)).unwrap(),
+        "u dd 1.5, -1, 4294967295\n"
+    );
+    let values = [Data::from(Expr::sym("a") + 2)];
+    assert_eq!(emit(|w| w.dw("z", &values)).unwrap(), "z dw (a + 2)\n");
+}
+
+#[test]
+fn extended_precision() {
+    assert_eq!(emit(|w| w.dt("x", &[1.0.into()])).unwrap(), "x dt 1.0\n");
+    let err = invalid(emit(|w| w.dt("y", &[1i64.into()])));
+    assert_eq!(err.kind, InvalidKind::IllegalOperand);
+}
+
+#[test]
+fn integers_must_fit() {
+    let err = invalid(emit(|w| w.dd("v", &[0x1_0000_0000u64.into()])));
+    assert_eq!(err.mnemonic, "dd");
+    assert_eq!(err.kind, InvalidKind::ImmediateTooWide);
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use common::{emit, invalid};
+use gen86::{
+    error::InvalidKind,
+    gp_regs::*,
+    operand::OSize,
+    vec_regs::*,
+    writer::{Bits, Condition, FpType, X86Writer},
+};
+
+#[test]
+fn valid_instruction_is_emitted() {
+    assert_eq!(emit(|w| w.add(EAX, EBX)).unwrap(), "    add eax, ebx\n");
+}
+
+#[test]
+fn memory_to_memory() {
+    let err = invalid(emit(|w| {
+        w.mov(RAX.mem() + OSize::QWord, RBX.mem() + OSize::QWord)
+    }));
+    assert_eq!(err.mnemonic, "mov");
+    assert_eq!(err.kind, InvalidKind::MemoryToMemory);
+}
+
+#[test]
+fn size_mismatch() {
+    let err = invalid(emit(|w| w.add(EAX, RBX)));
+    assert_eq!(err.mnemonic, "add");
+    assert_eq!(err.operands, ["eax", "rbx"]);
+    assert_eq!(err.kind, InvalidKind::SizeMismatch);
+}
+
+#[test]
+fn high_byte_with_rex_register() {
+    let err = invalid(emit(|w| w.mov(AH, R8B)));
+    assert_eq!(err.mnemonic, "mov");
+    assert_eq!(err.kind, InvalidKind::HighByteConflict);
+}
+
+#[test]
+fn count_jump_in_wrong_mode() {
+    let err = invalid(emit(|w| {
+        w.bits(Bits::Bits32)?;
+        w.jcc(Condition::RCXZ, "target")
+    }));
+    assert_eq!(err.mnemonic, "jrcxz");
+    assert_eq!(err.kind, InvalidKind::NotInMode(Bits::Bits32));
+}
+
+#[test]
+fn legacy_instruction_in_64bit_mode() {
+    let err = invalid(emit(|w| w.into_()));
+    assert_eq!(err.mnemonic, "into");
+    assert_eq!(err.kind, InvalidKind::NotInMode(Bits::Bits64));
+}
+
+#[test]
+fn fma_checks_mode() {
+    let err = invalid(emit(|w| {
+        w.bits(Bits::Bits32)?;
+        w.vfmadd231(FpType::PS, XMM8, XMM9, XMM10)
+    }));
+    assert_eq!(err.mnemonic, "vfmadd231");
+    assert_eq!(err.kind, InvalidKind::NotInMode(Bits::Bits32));
+}
+
+#[test]
+fn long_mode_only_forms() {
+    let err = invalid(emit(|w| {
+        w.bits(Bits::Bits32)?;
+        w.xsave64(EAX.mem())
+    }));
+    assert_eq!(err.kind, InvalidKind::NotInMode(Bits::Bits32));
+    let out = emit(|w| {
+        w.bits(Bits::Bits32)?;
+        w.rdpid(EAX)
+    });
+    assert_eq!(out.unwrap(), "bits 32\n    rdpid eax\n");
+    let err = invalid(emit(|w| w.rdpid(EAX)));
+    assert_eq!(err.kind, InvalidKind::NeedsQWord);
+}
+
+#[test]
+fn operands_are_rendered_in_assembler_syntax() {
+    let err = invalid(emit(|w| w.add(EAX, RBX.mem() + 8i64 + OSize::QWord)));
+    assert_eq!(
+        err.to_string(),
+        "add operand sizes do not match: eax, qword[rbx + 8]"
+    );
+}
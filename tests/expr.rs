@@ -0,0 +1,52 @@
+mod common;
+
+use common::{emit, label_errors};
+use gen86::{error::LabelError, expr::Expr, gp_regs::*, mem::Mem, writer::X86Writer};
+
+#[test]
+fn binary_expressions_are_parenthesised() {
+    let len = (Expr::here() - Expr::section_start()) * 2 + "x";
+    let out = emit(|w| w.equ_expr("len", &len)).unwrap();
+    assert_eq!(out, "len equ ((($ - $$) * 2) + x)\n");
+}
+
+#[test]
+fn operators() {
+    let mask = (Expr::sym("a") << 4 | 1) & 0xFF;
+    let out = emit(|w| w.mov(RAX, &mask)).unwrap();
+    assert_eq!(out, "    mov rax, (((a << 4) | 1) & 255)\n");
+}
+
+#[test]
+fn label_differences() {
+    let out = emit(|w| {
+        let start = w.new_label();
+        let end = w.new_label();
+        let size = Expr::from(&end) - &start;
+        w.bind(&start)?;
+        w.nop()?;
+        w.bind(&end)?;
+        w.mov(ECX, &size)
+    });
+    assert_eq!(
+        out.unwrap(),
+        "..@L0:\n    nop\n..@L1:\n    mov ecx, (..@L1 - ..@L0)\n"
+    );
+}
+
+#[test]
+fn displacement_expressions() {
+    let offset = Expr::sym("field") * 8;
+    let out = emit(|w| w.lea(RAX, Mem::new().displaced(&offset) + RBX)).unwrap();
+    assert_eq!(out, "    lea rax, [rbx + (field * 8)]\n");
+}
+
+#[test]
+fn symbols_in_expressions_are_references() {
+    let sum = Expr::sym("missing") + 1;
+    let errs = label_errors(emit(|w| {
+        w.mov(RAX, &sum)?;
+        w.finish()
+    }));
+    assert_eq!(errs, [LabelError::Undefined("missing".to_string())]);
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use common::{emit, invalid};
+use gen86::{
+    error::InvalidKind,
+    gp_regs::*,
+    operand::OSize,
+    writer::{Feature, X86Writer},
+};
+
+#[test]
+fn sign_extended_imm32() {
+    assert_eq!(emit(|w| w.add(RAX, -1)).unwrap(), "    add rax, -1\n");
+    assert_eq!(
+        emit(|w| w.add(RAX, 0x7FFF_FFFF)).unwrap(),
+        "    add rax, 2147483647\n"
+    );
+    assert_eq!(
+        invalid(emit(|w| w.add(RAX, 0x8000_0000u32))).kind,
+        InvalidKind::ImmediateTooWide
+    );
+    assert_eq!(
+        invalid(emit(|w| w.add(RAX, 0x1_0000_0000i64))).kind,
+        InvalidKind::ImmediateTooWide
+    );
+}
+
+#[test]
+fn unsigned_qword_folds_to_signed() {
+    assert_eq!(emit(|w| w.and(RAX, u64::MAX)).unwrap(), "    and rax, -1\n");
+}
+
+#[test]
+fn narrow_destinations() {
+    assert_eq!(emit(|w| w.add(AL, 0xFF)).unwrap(), "    add al, 255\n");
+    assert_eq!(
+        invalid(emit(|w| w.add(AL, 0x100))).kind,
+        InvalidKind::ImmediateTooWide
+    );
+    assert_eq!(
+        invalid(emit(|w| w.add(AX, -0x8001))).kind,
+        InvalidKind::ImmediateTooWide
+    );
+    assert_eq!(
+        invalid(emit(|w| w.mov(EAX, 0x1_0000_0000i64))).kind,
+        InvalidKind::ImmediateTooWide
+    );
+    assert_eq!(
+        invalid(emit(|w| w.mov(RAX.mem() + OSize::DWord, 0x1_0000_0000i64))).kind,
+        InvalidKind::ImmediateTooWide
+    );
+}
+
+#[test]
+fn mov_r64_takes_imm64() {
+    let out = emit(|w| w.mov(RAX, 0x1234_5678_9ABCi64)).unwrap();
+    assert_eq!(out, "    mov rax, 20015998343868\n");
+    assert_eq!(
+        invalid(emit(|w| w.mov(RAX.mem() + OSize::QWord, 0x1_0000_0000i64))).kind,
+        InvalidKind::ImmediateTooWide
+    );
+}
+
+#[test]
+fn mov_r64_narrowing_is_opt_in() {
+    assert_eq!(emit(|w| w.mov(RAX, 5)).unwrap(), "    mov rax, 5\n");
+    let out = emit(|w| {
+        w.enable(Feature::NarrowMovImm);
+        w.mov(RAX, 5)?;
+        w.mov(RAX, -1)
+    });
+    assert_eq!(out.unwrap(), "    mov eax, 5\n    mov rax, -1\n");
+}
@@ -0,0 +1,33 @@
+mod common;
+
+use common::{emit, invalid};
+use gen86::{
+    error::InvalidKind,
+    gp_regs::*,
+    implicit::{emit_rdrand_retry, emit_rdseed_retry},
+};
+
+#[test]
+fn rdseed_retry_pauses_between_attempts() {
+    let out = emit(|w| emit_rdseed_retry(w, RAX, RCX, 10, "fail")).unwrap();
+    assert_eq!(
+        out,
+        "    mov ecx, 10
+..@L0:
+    rdseed rax
+    jc ..@L1
+    pause
+    dec ecx
+    jnz ..@L0
+    jmp fail
+..@L1:
+"
+    );
+}
+
+#[test]
+fn zero_retries_are_rejected() {
+    let err = invalid(emit(|w| emit_rdrand_retry(w, RAX, RCX, 0, "fail")));
+    assert_eq!(err.mnemonic, "rdrand");
+    assert_eq!(err.kind, InvalidKind::IllegalOperand);
+}
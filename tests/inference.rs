@@ -0,0 +1,77 @@
+mod common;
+
+use common::{emit, invalid};
+use gen86::{
+    error::InvalidKind,
+    gp_regs::*,
+    operand::OSize,
+    writer::{Bits, Condition, X86Writer},
+};
+
+#[test]
+fn memory_takes_the_register_size() {
+    assert_eq!(
+        emit(|w| w.mov(RAX.mem(), RBX)).unwrap(),
+        "    mov qword[rax], rbx\n"
+    );
+    assert_eq!(
+        emit(|w| w.add(ECX, RAX.mem())).unwrap(),
+        "    add ecx, dword[rax]\n"
+    );
+}
+
+#[test]
+fn implicit_sizes() {
+    let out = emit(|w| w.setcc(Condition::E, RAX.mem())).unwrap();
+    assert_eq!(out, "    sete byte[rax]\n");
+    assert_eq!(
+        emit(|w| w.push(RAX.mem())).unwrap(),
+        "    push qword[rax]\n"
+    );
+    let out = emit(|w| {
+        w.bits(Bits::Bits32)?;
+        w.push(EAX.mem())
+    });
+    assert_eq!(out.unwrap(), "bits 32\n    push dword[eax]\n");
+}
+
+#[test]
+fn explicit_size_is_kept() {
+    let out = emit(|w| w.add(RAX.mem() + OSize::Word, 1)).unwrap();
+    assert_eq!(out, "    add word[rax], 1\n");
+}
+
+#[test]
+fn immediates_need_a_size() {
+    for result in [
+        emit(|w| w.add(RAX.mem(), 1)),
+        emit(|w| w.mov(RAX.mem(), 1)),
+        emit(|w| w.shl(RAX.mem(), 1)),
+    ] {
+        assert_eq!(invalid(result).kind, InvalidKind::AmbiguousSize);
+    }
+}
+
+#[test]
+fn unsized_single_and_extending_operands() {
+    assert_eq!(
+        invalid(emit(|w| w.inc(RAX.mem()))).kind,
+        InvalidKind::AmbiguousSize
+    );
+    assert_eq!(
+        invalid(emit(|w| w.movzx(EAX, RBX.mem()))).kind,
+        InvalidKind::AmbiguousSize
+    );
+    assert_eq!(
+        invalid(emit(|w| w.crc32(EAX, RBX.mem()))).kind,
+        InvalidKind::AmbiguousSize
+    );
+}
+
+#[test]
+fn cmov_and_imul_need_register_destinations() {
+    let err = invalid(emit(|w| w.cmov(Condition::E, RAX.mem(), RBX)));
+    assert_eq!(err.kind, InvalidKind::IllegalOperand);
+    let err = invalid(emit(|w| w.imul2(RAX.mem(), RBX)));
+    assert_eq!(err.kind, InvalidKind::IllegalOperand);
+}
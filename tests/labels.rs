@@ -0,0 +1,142 @@
+mod common;
+
+use common::{emit, label_errors};
+use gen86::{
+    Error,
+    error::LabelError,
+    gp_regs::*,
+    mem::Mem,
+    writer::{Condition, X86Writer},
+};
+
+#[test]
+fn clean_program() {
+    let out = emit(|w| {
+        w.label("f")?;
+        w.jcc(Condition::E, ".done")?;
+        w.inc(RAX)?;
+        w.label(".done")?;
+        w.ret()?;
+        w.finish()
+    });
+    assert_eq!(
+        out.unwrap(),
+        "f:\n    je .done\n    inc rax\n.done:\n    ret\n"
+    );
+}
+
+#[test]
+fn undefined_duplicate_and_unused() {
+    let errs = label_errors(emit(|w| {
+        w.label("f")?;
+        w.jmp("g")?;
+        w.label(".unused")?;
+        w.label("h")?;
+        w.label("h")?;
+        w.finish()
+    }));
+    assert_eq!(
+        errs,
+        [
+            LabelError::Undefined("g".to_string()),
+            LabelError::Duplicate("h".to_string()),
+            LabelError::UnusedLocal("f.unused".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn locals_are_scoped_to_the_enclosing_label() {
+    let errs = label_errors(emit(|w| {
+        w.label("f")?;
+        w.label(".loop")?;
+        w.jmp(".loop")?;
+        w.label("g")?;
+        w.jmp(".loop")?;
+        w.finish()
+    }));
+    assert_eq!(errs, [LabelError::Undefined("g.loop".to_string())]);
+}
+
+#[test]
+fn memory_references_are_tracked() {
+    let errs = label_errors(emit(|w| {
+        w.mov(RAX, Mem::new().rip_relative() + "table")?;
+        w.finish()
+    }));
+    assert_eq!(errs, [LabelError::Undefined("table".to_string())]);
+}
+
+#[test]
+fn externs_suppress_undefined() {
+    let out = emit(|w| {
+        w.r#extern("puts")?;
+        w.extern_weak("maybe")?;
+        w.call("puts")?;
+        w.call("maybe")?;
+        w.finish()
+    });
+    assert!(out.is_ok());
+}
+
+#[test]
+fn generated_labels_are_not_locals() {
+    let errs = label_errors(emit(|w| {
+        w.label("f")?;
+        let skip = w.new_label();
+        w.bind(&skip)?;
+        w.label(".loop")?;
+        w.jmp(".loop")?;
+        w.label(".unused")?;
+        w.finish()
+    }));
+    assert_eq!(errs, [LabelError::UnusedLocal("f.unused".to_string())]);
+}
+
+#[test]
+fn unclosed_function() {
+    let errs = label_errors(emit(|w| {
+        w.begin_function("f")?;
+        w.ret()?;
+        w.finish()
+    }));
+    assert_eq!(errs, [LabelError::UnclosedFunction("f".to_string())]);
+}
+
+#[test]
+fn unlabelled_data_keeps_the_scope() {
+    let out = emit(|w| {
+        w.label("f")?;
+        w.jmp(".x")?;
+        w.db("", &[b"a"])?;
+        w.db("", &[b"b"])?;
+        w.label(".x")?;
+        w.finish()
+    });
+    assert_eq!(out.unwrap(), "f:\n    jmp .x\n db \"a\"\n db \"b\"\n.x:\n");
+}
+
+#[test]
+fn nested_function_names_the_inner_function() {
+    let result = emit(|w| {
+        w.begin_function("outer")?;
+        w.begin_function("inner")
+    });
+    let Err(Error::Label(err)) = result else {
+        panic!("expected a label error, got {result:?}");
+    };
+    assert_eq!(err, LabelError::NestedFunction("inner".to_string()));
+}
+
+#[test]
+fn locals_after_end_function_scope_to_the_end_label() {
+    let out = emit(|w| {
+        w.begin_function("f")?;
+        w.ret()?;
+        w.end_function()?;
+        w.label(".x")?;
+        w.jmp("f.end.x")?;
+        w.finish()
+    });
+    assert_eq!(out.unwrap(), "f:\n    ret\nf.end:\n.x:\n    jmp f.end.x\n");
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use common::{emit, invalid};
+use gen86::{
+    error::InvalidKind,
+    gp_regs::*,
+    mem::{Mem, MemError, Scale},
+    writer::{Bits, X86Writer},
+};
+
+#[test]
+fn scale_one_index_becomes_base() {
+    let mem = (Mem::new() + (RSP, Scale::One))
+        .validate(Bits::Bits64)
+        .unwrap();
+    assert_eq!(mem.base, Some(RSP));
+    assert_eq!(mem.index, None);
+}
+
+#[test]
+fn scale_two_index_becomes_base_plus_index() {
+    let out = emit(|w| w.lea(RAX, Mem::new() + (RCX, Scale::Two))).unwrap();
+    assert_eq!(out, "    lea rax, [rcx + rcx]\n");
+}
+
+#[test]
+fn stack_pointer_index_is_swapped_to_base() {
+    let out = emit(|w| w.lea(RAX, RBX.mem().indexed(RSP))).unwrap();
+    assert_eq!(out, "    lea rax, [rsp + rbx]\n");
+}
+
+#[test]
+fn stack_pointer_index() {
+    let mem = RBX.mem() + (RSP, Scale::Four);
+    assert_eq!(mem.validate(Bits::Bits64), Err(MemError::StackPointerIndex));
+}
+
+#[test]
+fn mixed_widths() {
+    let mem = RAX.mem().indexed(ECX);
+    let err = MemError::MixedWidths(RSize::QWord, RSize::DWord);
+    assert_eq!(mem.validate(Bits::Bits64), Err(err));
+}
+
+#[test]
+fn illegal_address_sizes() {
+    let err = MemError::IllegalAddressSize(RSize::Word);
+    assert_eq!(BX.mem().validate(Bits::Bits64), Err(err));
+    let err = MemError::IllegalAddressSize(RSize::QWord);
+    assert_eq!(RBX.mem().validate(Bits::Bits32), Err(err));
+    let err = MemError::IllegalAddressSize(RSize::Byte);
+    assert_eq!(BL.mem().validate(Bits::Bits32), Err(err));
+}
+
+#[test]
+fn invalid_16bit_form() {
+    let mem = AX.mem().indexed(BX);
+    assert_eq!(mem.validate(Bits::Bits16), Err(MemError::Invalid16BitForm));
+}
+
+#[test]
+fn displacement_too_wide() {
+    let mem = RAX.mem() + 0x1_0000_0000i64;
+    let err = MemError::DisplacementTooWide(0x1_0000_0000);
+    assert_eq!(mem.validate(Bits::Bits64), Err(err));
+}
+
+#[test]
+fn writer_reports_mem_errors() {
+    let err = invalid(emit(|w| w.lea(RAX, RBX.mem() + (RSP, Scale::Two))));
+    assert_eq!(err.mnemonic, "lea");
+    assert_eq!(err.kind, InvalidKind::Mem(MemError::StackPointerIndex));
+}
@@ -0,0 +1,179 @@
+mod common;
+
+use common::{emit, invalid};
+use gen86::{
+    error::InvalidKind,
+    gp_regs::*,
+    switch::{Strategy, Switch, emit_switch},
+    writer::X86Writer,
+};
+
+#[test]
+fn relative_table_zero_extends_dword_values() {
+    let switch = Switch::new(EDI, RAX, "d")
+        .case(0, "a")
+        .case(1, "b")
+        .case(2, "c")
+        .case(3, "e")
+        .case(4, "a");
+    assert_eq!(switch.strategy(), Strategy::Table);
+    let out = emit(|w| emit_switch(w, &switch)).unwrap();
+    assert_eq!(
+        out,
+        "    mov edi, edi
+    cmp edi, 4
+    ja d
+    lea rax, [rel ..@L0]
+    movsxd rdi, dword[rax + rdi * 4]
+    add rax, rdi
+    jmp rax
+section .rodata
+..@L0:
+ dd (a - ..@L0), (b - ..@L0), (c - ..@L0), (e - ..@L0), (a - ..@L0)
+section .text
+"
+    );
+}
+
+#[test]
+fn absolute_table_rebases_value() {
+    let switch = Switch::new(RDI, RAX, "d")
+        .case(10, "a")
+        .case(11, "b")
+        .case(12, "c")
+        .case(13, "e")
+        .absolute();
+    assert_eq!(switch.strategy(), Strategy::Table);
+    let out = emit(|w| emit_switch(w, &switch)).unwrap();
+    assert_eq!(
+        out,
+        "    sub rdi, 10
+    cmp rdi, 3
+    ja d
+    lea rax, [rel ..@L0]
+    jmp qword[rax + rdi * 8]
+section .rodata
+..@L0:
+ dq a, b, c, e
+section .text
+"
+    );
+}
+
+#[test]
+fn table_label_is_bound() {
+    let switch = Switch::new(RDI, RAX, "d")
+        .case(0, "a")
+        .case(1, "b")
+        .case(2, "c")
+        .case(3, "e");
+    let out = emit(|w| {
+        w.label("f")?;
+        emit_switch(w, &switch)?;
+        for target in ["a", "b", "c", "d", "e"] {
+            w.label(target)?;
+        }
+        w.finish()
+    });
+    assert!(out.is_ok());
+}
+
+#[test]
+fn sparse_cases_use_binary_search() {
+    let switch = Switch::new(RDI, RAX, "d")
+        .case(1, "a")
+        .case(100, "b")
+        .case(1000, "c")
+        .case(10000, "e")
+        .case(100000, "f");
+    assert_eq!(switch.strategy(), Strategy::Search);
+    let out = emit(|w| emit_switch(w, &switch)).unwrap();
+    assert_eq!(
+        out,
+        "    cmp rdi, 1000
+    je c
+    jl ..@L0
+    cmp rdi, 10000
+    je e
+    cmp rdi, 100000
+    je f
+    jmp d
+..@L0:
+    cmp rdi, 1
+    je a
+    cmp rdi, 100
+    je b
+    jmp d
+"
+    );
+}
+
+#[test]
+fn few_targets_use_bit_test() {
+    let switch = Switch::new(EDI, RAX, "d")
+        .case(10, "a")
+        .case(12, "b")
+        .case(40, "a");
+    assert_eq!(switch.strategy(), Strategy::BitTest);
+    let out = emit(|w| emit_switch(w, &switch)).unwrap();
+    assert_eq!(
+        out,
+        "    mov edi, edi
+    sub edi, 10
+    cmp edi, 30
+    ja d
+    mov rax, 1073741825
+    bt rax, rdi
+    jc a
+    mov rax, 4
+    bt rax, rdi
+    jc b
+    jmp d
+"
+    );
+}
+
+#[test]
+fn narrow_values_are_rejected() {
+    let err = invalid(emit(|w| emit_switch(w, &Switch::new(DI, RAX, "d"))));
+    assert_eq!(err.mnemonic, "switch");
+    assert_eq!(err.kind, InvalidKind::IllegalOperand);
+}
+
+#[test]
+fn dword_keys_are_ordered_as_signed_32_bit() {
+    let switch = Switch::new(EDI, EAX, "d")
+        .case(0, "a")
+        .case(1, "b")
+        .case(5, "e")
+        .case(0x8000_0000, "c");
+    let out = emit(|w| emit_switch(w, &switch)).unwrap();
+    assert_eq!(
+        out,
+        "    cmp edi, 1
+    je b
+    jl ..@L0
+    cmp edi, 5
+    je e
+    jmp d
+..@L0:
+    cmp edi, -2147483648
+    je c
+    cmp edi, 0
+    je a
+    jmp d
+"
+    );
+}
+
+#[test]
+fn dword_keys_must_fit_and_be_distinct() {
+    let switch = Switch::new(EDI, EAX, "d").case(0x1_0000_0000, "a");
+    let err = invalid(emit(|w| emit_switch(w, &switch)));
+    assert_eq!(err.kind, InvalidKind::ImmediateTooWide);
+    let switch = Switch::new(EDI, EAX, "d")
+        .case(-1, "a")
+        .case(0xFFFF_FFFF, "b");
+    let err = invalid(emit(|w| emit_switch(w, &switch)));
+    assert_eq!(err.kind, InvalidKind::IllegalOperand);
+}
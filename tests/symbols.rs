@@ -0,0 +1,52 @@
+mod common;
+
+use common::emit;
+use gen86::{symbol::Symbol, writer::X86Writer};
+
+#[test]
+fn plain_global() {
+    assert_eq!(
+        emit(|w| w.global_symbol(&Symbol::new("p"))).unwrap(),
+        "global p\n"
+    );
+}
+
+#[test]
+fn kind_visibility_and_size() {
+    let out = emit(|w| {
+        w.global_symbol(&Symbol::new("f").function().hidden().sized_to_end())?;
+        w.global_symbol(&Symbol::new("d").data().sized(8))
+    });
+    assert_eq!(
+        out.unwrap(),
+        "global f:function hidden (f.end - f)\nglobal d:data 8\n"
+    );
+}
+
+#[test]
+fn notype_is_spelled_out_when_needed() {
+    let out = emit(|w| {
+        w.global_symbol(&Symbol::new("w").weak())?;
+        w.global_symbol(&Symbol::new("q").protected())?;
+        w.global_symbol(&Symbol::new("s").sized(4))
+    });
+    assert_eq!(
+        out.unwrap(),
+        "global w:notype weak\nglobal q:notype protected\nglobal s:notype 4\n"
+    );
+}
+
+#[test]
+fn size_to_end_refers_to_the_function_end() {
+    let out = emit(|w| {
+        w.global_symbol(&Symbol::new("f").function().sized_to_end())?;
+        w.begin_function("f")?;
+        w.ret()?;
+        w.end_function()?;
+        w.finish()
+    });
+    assert_eq!(
+        out.unwrap(),
+        "global f:function (f.end - f)\nf:\n    ret\nf.end:\n"
+    );
+}
@@ -0,0 +1,20 @@
+mod common;
+
+use common::emit;
+use gen86::{
+    gp_regs::*,
+    typed::{Gpr8, Gpr64, Imm32, M32, TypedWriter},
+};
+
+#[test]
+fn typed_forms() {
+    let out = emit(|w| {
+        w.movzx_r64_r8(Gpr64(RName::A), Gpr8(RName::B))?;
+        w.add_m32_imm32(M32(RCX.mem()), Imm32(-1))?;
+        w.shl_r64_imm8(Gpr64::try_from(R8).unwrap(), 255)
+    });
+    assert_eq!(
+        out.unwrap(),
+        "    movzx rax, bl\n    add dword[rcx], -1\n    shl r8, 255\n"
+    );
+}